@@ -0,0 +1,105 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Derive macros for [fast_loaded_dice_roller](https://crates.io/crates/fast_loaded_dice_roller).
+//! Enable the `derive` feature of that crate rather than depending on this one directly.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Expr, ExprLit, Fields, Lit, LitInt, Meta};
+
+/// Derive `WeightedSample` for an enum of unit variants, each weighted by a `#[weight = N]`
+/// attribute, where `N` is an integer literal.
+#[proc_macro_derive(WeightedSample, attributes(weight))]
+pub fn derive_weighted_sample(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(
+            input,
+            "`WeightedSample` can only be derived for enums.",
+        ));
+    };
+
+    let mut weights = Vec::with_capacity(data.variants.len());
+    let mut arms = Vec::with_capacity(data.variants.len());
+    for (index, variant) in data.variants.iter().enumerate() {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "`WeightedSample` can only be derived for enums of unit variants.",
+            ));
+        }
+        weights.push(weight(variant)?);
+        let ident = &variant.ident;
+        arms.push(quote!(#index => Self::#ident,));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::fast_loaded_dice_roller::WeightedSample for #name #type_generics
+            #where_clause
+        {
+            const WEIGHTS: &'static [usize] = &[#(#weights),*];
+
+            fn from_index(index: usize) -> Self {
+                match index {
+                    #(#arms)*
+                    _ => panic!("The index must be less than the number of variants."),
+                }
+            }
+        }
+    })
+}
+
+/// The integer literal of the single `#[weight = N]` attribute of `variant`.
+fn weight(variant: &syn::Variant) -> Result<&LitInt, Error> {
+    let mut weights = variant
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("weight"));
+    let (Some(attr), None) = (weights.next(), weights.next()) else {
+        return Err(Error::new_spanned(
+            variant,
+            "Every variant must have exactly one `#[weight = N]` attribute.",
+        ));
+    };
+    match &attr.meta {
+        Meta::NameValue(meta) => match &meta.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(weight),
+                ..
+            }) => Ok(weight),
+            value => Err(Error::new_spanned(value, "The weight must be an integer.")),
+        },
+        meta => Err(Error::new_spanned(
+            meta,
+            "The weight must be given as `#[weight = N]`.",
+        )),
+    }
+}
@@ -20,10 +20,40 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use clap::Parser;
+use std::{
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+};
+
+use clap::{Parser, ValueEnum};
+use fast_loaded_dice_roller as fldr;
 
 const DEFAULT_DISTRIBUTION: [usize; 5] = [0, 1, 2, 3, 4];
 const DEFAULT_ROLL_COUNT: usize = 100_000;
+const DEFAULT_MAX_WEIGHT: usize = 1_000;
+
+/// How samples are written to standard output.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// One decimal sample per line, when `--verbose` is given.
+    Text,
+
+    /// Every sample as a little-endian integer, with the histogram moved to standard error.
+    Binary,
+}
+
+/// The integer width of binary samples.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Width {
+    U32,
+    U64,
+}
+
+/// The magic bytes that open the optional header of binary output.
+const BINARY_MAGIC: &[u8; 4] = b"FLDR";
+
+/// The version of the binary output format.
+const BINARY_VERSION: u8 = 1;
 
 // Use macro and crate `clap` to parse command line arguments.
 #[derive(Parser)]
@@ -38,8 +68,287 @@ struct Arguments {
     #[arg(short, long, default_value_t = true)]
     print_histogram: bool,
 
-    #[arg(short, long, value_parser, num_args = 2..)]
+    #[arg(short, long, value_parser, num_args = 2.., conflicts_with_all = ["random_distribution", "batch"])]
     distribution: Option<Vec<usize>>,
+
+    /// Sample from a synthetic distribution with N buckets.
+    #[arg(long, value_name = "N", conflicts_with = "batch")]
+    random_distribution: Option<usize>,
+
+    /// The largest weight in a synthetic distribution.
+    #[arg(long, value_name = "M", default_value_t = DEFAULT_MAX_WEIGHT, requires = "random_distribution")]
+    max_weight: usize,
+
+    /// Shape a synthetic distribution by Zipf's law with exponent S.
+    #[arg(long, value_name = "S", requires = "random_distribution")]
+    zipf: Option<f64>,
+
+    /// Sample every named distribution in a JSON or CSV file and print a combined report.
+    #[arg(short, long, value_name = "FILE")]
+    batch: Option<PathBuf>,
+
+    /// Explore the distribution interactively, starting from the given or default weights.
+    #[arg(short, long, default_value_t = false, conflicts_with = "batch")]
+    interactive: bool,
+
+    /// How to write samples to standard output.
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// The integer width of binary samples.
+    #[arg(long, value_enum, default_value_t = Width::U32)]
+    width: Width,
+
+    /// Open binary output with a 16-byte header: `FLDR`, a version, the width in bytes, two zero
+    /// bytes, and the sample count as a little-endian u64.
+    #[arg(long, default_value_t = false)]
+    header: bool,
+}
+
+const REPL_HELP: &str = "\
+Commands:
+  weights <W> <W>...  Replace the distribution and clear the histograms
+  set <I> <W>         Change the weight of bucket I and clear the histograms
+  roll [N]            Sample N times (default 1) into the current histogram
+  hist                Print the current histogram
+  tree                Print the weights and exact probability of every bucket
+  save                Keep a copy of the current histogram and start a new one
+  compare             Compare the saved and current histograms with the exact distribution
+  help                Print this message
+  quit                Exit";
+
+/// The state of an interactive session.
+struct Session {
+    distribution: Vec<usize>,
+    generator: fldr::Generator,
+    current: fldr::histogram::Histogram,
+    saved: Option<fldr::histogram::Histogram>,
+}
+
+impl Session {
+    fn new(distribution: Vec<usize>) -> Self {
+        Self {
+            generator: fldr::Generator::new(&distribution),
+            current: fldr::histogram::Histogram::new(distribution.len()),
+            saved: None,
+            distribution,
+        }
+    }
+
+    /// Replace the distribution and clear the histograms, unless the new weights are invalid.
+    fn reweight(&mut self, weights: Vec<usize>) {
+        match fldr::Generator::try_new(&weights) {
+            Ok(generator) => {
+                self.generator = generator;
+                self.current = fldr::histogram::Histogram::new(weights.len());
+                self.saved = None;
+                self.distribution = weights;
+            }
+            Err(e) => println!("{e}"),
+        }
+    }
+
+    /// Print the saved and current histograms next to the exact distribution.
+    fn compare(&self) {
+        let exact = fldr::distance::empirical(&self.distribution);
+        let saved = self
+            .saved
+            .as_ref()
+            .map(fldr::histogram::Histogram::normalize);
+        let current = self.current.normalize();
+        println!(
+            "{:>5}  {:>8}  {:>8}  {:>8}",
+            "", "exact", "saved", "current"
+        );
+        for (i, p) in exact.iter().enumerate() {
+            let saved = saved
+                .as_ref()
+                .map_or_else(|| "-".to_owned(), |s| format!("{:.2}%", 100. * s[i]));
+            println!(
+                "{i:>5}  {:>7.2}%  {saved:>8}  {:>7.2}%",
+                100. * p,
+                100. * current[i]
+            );
+        }
+        for (name, histogram) in [
+            ("saved", self.saved.as_ref()),
+            ("current", Some(&self.current)),
+        ] {
+            if let Some(h) = histogram.filter(|h| h.total() > 0) {
+                println!(
+                    "{name}: {} rolls, total variation {:.6}, chi-squared {:.3}",
+                    h.total(),
+                    fldr::distance::total_variation(&self.generator, &h.normalize()),
+                    fldr::distance::chi_square(&self.generator, h.counts())
+                );
+            }
+        }
+    }
+}
+
+/// Read commands from standard input until it closes or the user quits.
+fn repl(distribution: Vec<usize>, coin: &mut impl fldr::FairCoin) {
+    let mut session = Session::new(distribution);
+    println!("{REPL_HELP}");
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        print!("> ");
+        std::io::stdout()
+            .flush()
+            .expect("Failed to flush standard output.");
+        let Some(Ok(line)) = lines.next() else {
+            break;
+        };
+        let mut words = line.split_whitespace();
+        let command = words.next();
+        let numbers: Option<Vec<usize>> = words.map(|w| w.parse().ok()).collect();
+
+        match (command, numbers.as_deref()) {
+            (None, _) => {}
+            (Some("weights"), Some(weights)) => session.reweight(weights.to_vec()),
+            (Some("set"), Some(&[i, w])) if i < session.distribution.len() => {
+                let mut weights = session.distribution.clone();
+                weights[i] = w;
+                session.reweight(weights);
+            }
+            (Some("roll"), Some(n @ (&[] | &[_]))) => {
+                let n = n.first().copied().unwrap_or(1);
+                let samples: Vec<_> = (0..n).map(|_| session.generator.sample(coin)).collect();
+                samples.iter().for_each(|&s| session.current.record(s));
+                if n <= 20 {
+                    println!("{samples:?}");
+                }
+                print!("{}", session.current);
+            }
+            (Some("hist"), _) => print!("{}", session.current),
+            (Some("tree"), _) => {
+                println!("Weights: {:?}", session.distribution);
+                print!("{}", session.generator.probability_table());
+            }
+            (Some("save"), _) => {
+                let fresh = fldr::histogram::Histogram::new(session.distribution.len());
+                session.saved = Some(std::mem::replace(&mut session.current, fresh));
+            }
+            (Some("compare"), _) => session.compare(),
+            (Some("help"), _) => println!("{REPL_HELP}"),
+            (Some("quit" | "exit"), _) => break,
+            (Some(command), _) => println!("Invalid use of `{command}`. Type `help` for usage."),
+        }
+    }
+}
+
+/// A named distribution and the number of times to sample it, as read from a batch file.
+struct Plan {
+    name: String,
+    weights: Vec<usize>,
+    rolls: Option<usize>,
+}
+
+/// Read the plans in a batch file.
+///
+/// A `.json` file holds an array of objects such as `{"name": "chest", "weights": [5, 3, 1],
+/// "rolls": 10000}`, where `rolls` is optional. Any other file is read as CSV, one plan per line
+/// as `name,rolls,weight,weight,...`, where `rolls` may be left empty. Blank lines and lines
+/// starting with `#` are ignored.
+fn read_plans(path: &Path) -> Result<Vec<Plan>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    if path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("json"))
+    {
+        let json: serde_json::Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+        let usize_of = |v: &serde_json::Value| v.as_u64().and_then(|n| usize::try_from(n).ok());
+        json.as_array()
+            .ok_or("The batch file must hold an array of plans.")?
+            .iter()
+            .enumerate()
+            .map(|(i, plan)| {
+                Ok(Plan {
+                    name: plan["name"]
+                        .as_str()
+                        .map_or_else(|| format!("#{i}"), str::to_owned),
+                    weights: plan["weights"]
+                        .as_array()
+                        .and_then(|w| w.iter().map(usize_of).collect())
+                        .ok_or(format!("Plan #{i} must have an array of integer weights."))?,
+                    rolls: usize_of(&plan["rolls"]),
+                })
+            })
+            .collect()
+    } else {
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|(i, line)| {
+                let error = || format!("Line {} must be `name,rolls,weight,weight,...`.", i + 1);
+                let mut fields = line.split(',').map(str::trim);
+                let name = fields.next().ok_or_else(error)?.to_owned();
+                let rolls = match fields.next().ok_or_else(error)? {
+                    "" => None,
+                    rolls => Some(rolls.parse().map_err(|_| error())?),
+                };
+                let weights = fields
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| error())?;
+                Ok(Plan {
+                    name,
+                    weights,
+                    rolls,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Sample every plan and print one line per plan comparing the results with the exact
+/// distribution, by total variation distance and Pearson's chi-squared statistic.
+fn run_batch(plans: &[Plan], default_rolls: usize, coin: &mut impl fldr::FairCoin) {
+    let width = plans.iter().map(|p| p.name.len()).max().unwrap_or(0).max(4);
+    println!(
+        "{:width$}  {:>7}  {:>10}  {:>10}  {:>12}",
+        "name", "buckets", "rolls", "tv", "chi-squared"
+    );
+    for plan in plans {
+        let rolls = plan.rolls.unwrap_or(default_rolls);
+        let generator = match fldr::Generator::try_new(&plan.weights) {
+            Ok(generator) => generator,
+            Err(e) => {
+                println!("{:width$}  {e}", plan.name);
+                continue;
+            }
+        };
+        let histogram = generator.sample_histogram(coin, rolls);
+        let tv = fldr::distance::total_variation(
+            &generator,
+            &fldr::distance::empirical(histogram.counts()),
+        );
+        println!(
+            "{:width$}  {:>7}  {:>10}  {:>10.6}  {:>12.3}",
+            plan.name,
+            plan.weights.len(),
+            rolls,
+            tv,
+            fldr::distance::chi_square(&generator, histogram.counts())
+        );
+    }
+}
+
+/// Create a synthetic distribution of `n` weights, each in `1..=max_weight`.
+fn random_distribution(
+    n: usize,
+    max_weight: usize,
+    zipf: Option<f64>,
+    rng: &mut impl rand::Rng,
+) -> Vec<usize> {
+    let max_weight = max_weight.max(1);
+    match zipf {
+        // The weight of the `k`th bucket is proportional to `1 / k^s`, with the first at `max_weight`.
+        Some(s) => (1..=n)
+            .map(|k| ((max_weight as f64 / (k as f64).powf(s)).round() as usize).max(1))
+            .collect(),
+        None => (0..n).map(|_| rng.gen_range(1..=max_weight)).collect(),
+    }
 }
 
 fn main() {
@@ -47,34 +356,82 @@ fn main() {
     let args = Arguments::parse();
 
     // Setup simple PRNG for coin flips.
-    let mut rng = fast_loaded_dice_roller::rand::RngCoin::default();
+    let mut rng = fldr::rand::RngCoin::default();
+
+    // Sample every plan of a batch file instead of a single distribution.
+    if let Some(path) = args.batch {
+        match read_plans(&path) {
+            Ok(plans) => run_batch(&plans, args.roll_count, &mut rng),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
 
     // Setup parameters of the test sampling.
     let distribution = if let Some(dist) = args.distribution {
         dist
+    } else if let Some(n) = args.random_distribution {
+        random_distribution(n, args.max_weight, args.zipf, &mut rand::thread_rng())
     } else {
         DEFAULT_DISTRIBUTION.to_vec()
     };
-    let mut histogram = distribution.iter().map(|_| 0usize).collect::<Vec<_>>();
+    if args.interactive {
+        repl(distribution, &mut rng);
+        return;
+    }
+    let mut histogram = fldr::histogram::Histogram::new(distribution.len());
     let roll_count = args.roll_count;
     let verbose = args.verbose;
     let print_histogram = args.print_histogram;
+    let binary = args.format == Format::Binary;
+    if binary && args.width == Width::U32 && u32::try_from(distribution.len() - 1).is_err() {
+        eprintln!("The distribution has too many buckets for `--width u32`.");
+        std::process::exit(1);
+    }
+
+    // Buffer standard output, which matters for the throughput of binary output.
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    if binary && args.header {
+        let width = match args.width {
+            Width::U32 => 4,
+            Width::U64 => 8,
+        };
+        out.write_all(BINARY_MAGIC)
+            .and_then(|()| out.write_all(&[BINARY_VERSION, width, 0, 0]))
+            .and_then(|()| out.write_all(&(roll_count as u64).to_le_bytes()))
+            .expect("Failed to write to standard output.");
+    }
 
     // Let 'er roll!
-    let roller = fast_loaded_dice_roller::Generator::new(&distribution);
+    let roller = fldr::Generator::new(&distribution);
     for _ in 0..roll_count {
         let s = roller.sample(&mut rng);
-        if verbose {
-            println!("{s}");
-        }
-        histogram[s] += 1;
+        let written = match (binary, args.width) {
+            // The bucket count was checked to fit, so the conversion is lossless.
+            (true, Width::U32) => out.write_all(&(s as u32).to_le_bytes()),
+            (true, Width::U64) => out.write_all(&(s as u64).to_le_bytes()),
+            (false, _) if verbose => writeln!(out, "{s}"),
+            (false, _) => Ok(()),
+        };
+        written.expect("Failed to write to standard output.");
+        histogram.record(s);
     }
+    out.flush().expect("Failed to flush standard output.");
+    drop(out);
 
-    // Print the results of the repeated sampling as a histogram.
+    // Print the results of the repeated sampling as a histogram, keeping binary output clean.
     if print_histogram {
-        println!(
-            "Total rolls: {roll_count}\nInitial distribution: {:?}\nHistogram results: {:?}",
-            distribution, histogram
+        let report = format!(
+            "Total rolls: {roll_count}\nInitial distribution: {:?}\nHistogram results:\n{histogram}",
+            distribution
         );
+        if binary {
+            eprintln!("{report}");
+        } else {
+            println!("{report}");
+        }
     }
 }
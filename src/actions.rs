@@ -0,0 +1,115 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Running one of several handlers at random, chosen by weight.
+
+use alloc::{boxed::Box, vec::Vec};
+
+use crate::{FairCoin, Generator};
+
+/// A set of weighted handlers, one of which runs per invocation.
+///
+/// ```
+/// use fast_loaded_dice_roller as fldr;
+/// # struct AlternatingCoin(bool);
+/// # impl fldr::FairCoin for AlternatingCoin {
+/// #     fn flip(&mut self) -> bool {
+/// #         self.0 = !self.0;
+/// #         self.0
+/// #     }
+/// # }
+/// # let mut coin = AlternatingCoin(false);
+///
+/// let mut patrols = 0;
+/// let mut actions = fldr::actions::WeightedActions::new()
+///     .with(70, || "idle")
+///     .with(20, || {
+///         patrols += 1;
+///         "patrol"
+///     })
+///     .with(10, || "attack");
+/// for _ in 0..10 {
+///     actions.run(&mut coin);
+/// }
+/// ```
+pub struct WeightedActions<'a, T> {
+    weights: Vec<usize>,
+    handlers: Vec<Box<dyn FnMut() -> T + 'a>>,
+    generator: Option<Generator>,
+}
+
+impl<'a, T> WeightedActions<'a, T> {
+    /// Create an empty set of handlers.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            weights: Vec::new(),
+            handlers: Vec::new(),
+            generator: None,
+        }
+    }
+
+    /// Add a handler that runs with probability proportional to `weight`.
+    #[must_use]
+    pub fn with(mut self, weight: usize, handler: impl FnMut() -> T + 'a) -> Self {
+        self.push(weight, handler);
+        self
+    }
+
+    /// Add a handler that runs with probability proportional to `weight`.
+    pub fn push(&mut self, weight: usize, handler: impl FnMut() -> T + 'a) {
+        self.weights.push(weight);
+        self.handlers.push(Box::new(handler));
+        self.generator = None;
+    }
+
+    /// The number of handlers.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    /// Whether there are no handlers.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// Run one handler chosen at random using a given `FairCoin`, and return its result.
+    /// # Panics
+    /// Will panic if less than two handlers have a non-zero weight, or if the sum of the weights
+    /// overflows a `usize`.
+    pub fn run(&mut self, fair_coin: &mut impl FairCoin) -> T {
+        let weights = &self.weights;
+        let index = self
+            .generator
+            .get_or_insert_with(|| Generator::new(weights))
+            .sample(fair_coin);
+        (self.handlers[index])()
+    }
+}
+
+impl<T> Default for WeightedActions<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,152 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Choosing moves for simulated annealing, where the weights of the moves follow their scores and
+//! sharpen as the temperature decays.
+
+use crate::{choice::Choice, distributions::quantize, FairCoin};
+
+/// Samples moves with probability proportional to `exp(score / temperature)`, quantized to
+/// `precision_bits` bits, so that better moves dominate as the temperature falls.
+///
+/// The DDG tree is rebuilt lazily, on the first sample after the scores or temperature change, and
+/// not at all if the quantized weights are unchanged, which is common once the temperature is
+/// high enough that every move is equally likely or low enough that only the best moves remain.
+#[derive(Clone)]
+pub struct AnnealingSelector {
+    scores: Vec<f64>,
+    temperature: f64,
+    precision_bits: u32,
+    weights: Vec<usize>,
+    choice: Option<Choice>,
+    stale: bool,
+}
+
+impl AnnealingSelector {
+    /// Create a selector over moves with the given scores, starting at `temperature`.
+    /// # Panics
+    /// Will panic if there are no scores, if a score is not finite, if the temperature is not
+    /// positive and finite, or if `precision_bits` is too large for a `usize`.
+    #[must_use]
+    pub fn new(scores: Vec<f64>, temperature: f64, precision_bits: u32) -> Self {
+        assert!(!scores.is_empty(), "There must be at least one move.");
+        assert!(
+            scores.iter().all(|s| s.is_finite()),
+            "Every score must be finite."
+        );
+        let mut selector = Self {
+            scores,
+            temperature: 1.,
+            precision_bits,
+            weights: Vec::new(),
+            choice: None,
+            stale: true,
+        };
+        selector.set_temperature(temperature);
+        selector
+    }
+
+    /// The scores of the moves.
+    #[must_use]
+    pub fn scores(&self) -> &[f64] {
+        &self.scores
+    }
+
+    /// Change the score of move `i`.
+    /// # Panics
+    /// Will panic if `i` is out of bounds or the score is not finite.
+    pub fn set_score(&mut self, i: usize, score: f64) {
+        assert!(score.is_finite(), "Every score must be finite.");
+        self.scores[i] = score;
+        self.stale = true;
+    }
+
+    /// The current temperature.
+    #[must_use]
+    pub fn temperature(&self) -> f64 {
+        self.temperature
+    }
+
+    /// Change the temperature, e.g. to follow a custom schedule.
+    /// # Panics
+    /// Will panic if the temperature is not positive and finite.
+    pub fn set_temperature(&mut self, temperature: f64) {
+        assert!(
+            temperature > 0. && temperature.is_finite(),
+            "The temperature must be positive and finite."
+        );
+        self.temperature = temperature;
+        self.stale = true;
+    }
+
+    /// Multiply the temperature by `factor`, following a geometric cooling schedule.
+    /// # Panics
+    /// Will panic if `factor` is not in `(0, 1]`, or if the temperature underflows to zero.
+    pub fn cool(&mut self, factor: f64) {
+        assert!(
+            factor > 0. && factor <= 1.,
+            "The cooling factor must be in (0, 1]."
+        );
+        self.set_temperature(self.temperature * factor);
+    }
+
+    /// The quantized weights of the moves at the current scores and temperature.
+    #[must_use]
+    pub fn weights(&mut self) -> &[usize] {
+        self.rebuild();
+        &self.weights
+    }
+
+    /// Sample the index of a move, using a given `FairCoin`.
+    pub fn sample(&mut self, fair_coin: &mut impl FairCoin) -> usize {
+        self.rebuild();
+        self.choice
+            .as_ref()
+            .expect("the choice was just rebuilt")
+            .sample(fair_coin)
+    }
+
+    /// Bring the weights and DDG tree up to date with the scores and temperature.
+    fn rebuild(&mut self) {
+        if !self.stale {
+            return;
+        }
+        self.stale = false;
+
+        // Shift the scores so that the best move has a mass of one and no mass overflows.
+        let best = self
+            .scores
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        let weights = quantize(
+            self.scores
+                .iter()
+                .map(|s| ((s - best) / self.temperature).exp()),
+            self.precision_bits,
+        );
+        if weights != self.weights {
+            self.choice = Choice::new(&weights);
+            self.weights = weights;
+        }
+    }
+}
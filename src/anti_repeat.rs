@@ -0,0 +1,139 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Sampling that avoids repeating recently sampled items.
+
+use alloc::collections::VecDeque;
+
+use crate::{uniform::Uniform, FairCoin, Generator};
+
+/// A stateful sampler that excludes or down-weights the most recently sampled indices, for
+/// playlists, ambient sounds, and content rotation where immediate repeats feel wrong.
+///
+/// Recent indices are handled by exact rejection: a sample from the underlying generator that
+/// lands on a recent index is kept only with the configured probability, and otherwise redrawn.
+/// The result is exactly the distribution with each recent weight multiplied by the penalty and
+/// renormalized, without rebuilding the DDG tree.
+#[derive(Clone)]
+pub struct AntiRepeat {
+    generator: Generator,
+    history: VecDeque<usize>,
+    history_len: usize,
+    acceptance: Acceptance,
+}
+
+/// Whether a recently sampled index may be sampled again.
+#[derive(Clone)]
+enum Acceptance {
+    Never,
+    Sometimes {
+        numerator: usize,
+        denominator: usize,
+    },
+}
+
+impl AntiRepeat {
+    /// Create a sampler that never returns any of the last `history_len` sampled indices.
+    /// # Panics
+    /// Will panic if `history_len` is not less than the number of buckets with non-zero weight,
+    /// since every index could then be excluded.
+    #[must_use]
+    pub fn exclude(generator: Generator, history_len: usize) -> Self {
+        assert!(
+            history_len < generator.support().len(),
+            "The history must be shorter than the support of the distribution."
+        );
+        Self {
+            generator,
+            history: VecDeque::with_capacity(history_len),
+            history_len,
+            acceptance: Acceptance::Never,
+        }
+    }
+
+    /// Create a sampler that multiplies the weight of each of the last `history_len` sampled
+    /// indices by `numerator / denominator`.
+    /// # Panics
+    /// Will panic if `numerator / denominator` is not in `(0, 1]`.
+    #[must_use]
+    pub fn down_weight(
+        generator: Generator,
+        history_len: usize,
+        numerator: usize,
+        denominator: usize,
+    ) -> Self {
+        assert!(
+            0 < numerator && numerator <= denominator,
+            "The penalty must be in (0, 1]."
+        );
+        Self {
+            generator,
+            history: VecDeque::with_capacity(history_len),
+            history_len,
+            acceptance: Acceptance::Sometimes {
+                numerator,
+                denominator,
+            },
+        }
+    }
+
+    /// The most recently sampled indices, oldest first.
+    #[must_use]
+    pub fn history(&self) -> &VecDeque<usize> {
+        &self.history
+    }
+
+    /// Forget the sampling history.
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+
+    /// Sample a random index, taking the recent history into account, and record it.
+    pub fn sample(&mut self, fair_coin: &mut impl FairCoin) -> usize {
+        let i = loop {
+            let i = self.generator.sample(fair_coin);
+            if !self.history.contains(&i) {
+                break i;
+            }
+            // Accept the repeat with exactly the penalty's probability, as `sample_adjusted` does.
+            if let Acceptance::Sometimes {
+                numerator,
+                denominator,
+            } = self.acceptance
+            {
+                if numerator == denominator
+                    || Uniform::new(denominator).sample(fair_coin) < numerator
+                {
+                    break i;
+                }
+            }
+        };
+
+        if self.history_len > 0 {
+            if self.history.len() == self.history_len {
+                self.history.pop_front();
+            }
+            self.history.push_back(i);
+        }
+        i
+    }
+}
@@ -0,0 +1,354 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Constructors that trade exactness for a smaller DDG tree.
+//!
+//! The depth of a DDG tree is the number of bits needed to write the sum of the weights, so a
+//! distribution with a 64-bit sum needs a 64-level tree. When that precision isn't needed, the
+//! weights can be rescaled to a smaller power of two, which bounds the depth of the tree and also
+//! removes the padding label, so sampling never takes a back-edge.
+
+use crate::Generator;
+
+/// An approximate generator together with the error introduced by approximating.
+#[derive(Clone)]
+pub struct Approximation {
+    /// The generator for the approximated distribution.
+    pub generator: Generator,
+
+    /// The largest absolute difference between the probability of any bucket in the requested
+    /// distribution and in the approximated distribution.
+    pub max_bias: f64,
+
+    /// The Kullback-Leibler divergence, in bits, of the approximated distribution from the
+    /// requested one, i.e. the expected number of bits of evidence per sample that the samples
+    /// were not drawn from the requested distribution. It is infinite if a bucket with a non-zero
+    /// requested probability was rounded to a weight of zero.
+    pub kl_divergence: f64,
+}
+
+/// The f-divergence minimized by `Generator::optimal_approximation`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Divergence {
+    /// The Kullback-Leibler divergence of the approximation from the target. Every bucket with a
+    /// non-zero target probability keeps a non-zero weight whenever the precision allows it.
+    KullbackLeibler,
+
+    /// The squared Hellinger distance between the target and the approximation.
+    Hellinger,
+
+    /// The total variation distance between the target and the approximation.
+    TotalVariation,
+}
+
+impl Divergence {
+    /// The contribution of a single bucket with target probability `p` and approximate
+    /// probability `q` to the divergence.
+    fn term(self, p: f64, q: f64) -> f64 {
+        match self {
+            Self::KullbackLeibler if p == 0. => 0.,
+            Self::KullbackLeibler if q == 0. => f64::INFINITY,
+            Self::KullbackLeibler => p * (p / q).ln(),
+            Self::Hellinger => (p.sqrt() - q.sqrt()).powi(2),
+            Self::TotalVariation => (p - q).abs() / 2.,
+        }
+    }
+}
+
+impl Generator {
+    /// Create a generator whose DDG tree has at most `max_depth` levels. If the sum of the weights
+    /// exceeds `2^max_depth`, the weights are rescaled to sum to exactly `2^max_depth`, rounding
+    /// with the largest remainder method so that no bucket's probability moves by more than
+    /// `2^-max_depth`. Buckets whose weight rounds to zero can no longer be sampled.
+    /// # Panics
+    /// Will panic if `distribution` is invalid for `Generator::new`, if `2^max_depth` does not fit
+    /// in a `usize`, or if fewer than two weights remain non-zero after rounding.
+    #[must_use]
+    pub fn with_max_depth(distribution: &[usize], max_depth: u32) -> Approximation {
+        assert!(
+            max_depth < usize::BITS,
+            "The maximum depth must be less than the bit width of a `usize`."
+        );
+        let target = 1usize << max_depth;
+        let sum = distribution
+            .iter()
+            .try_fold(0usize, |sum, &w| sum.checked_add(w))
+            .expect("The sum of the weights must fit in a `usize`.");
+        if sum <= target {
+            return Approximation {
+                generator: Generator::new(distribution),
+                max_bias: 0.,
+                kl_divergence: 0.,
+            };
+        }
+
+        let weights = largest_remainder(distribution, sum, target);
+        Approximation {
+            max_bias: max_bias(distribution, sum, &weights, target),
+            kl_divergence: kl_divergence(
+                distribution.iter().map(|&w| w as f64 / sum as f64),
+                &weights,
+                target,
+            ),
+            generator: Generator::new(&weights),
+        }
+    }
+}
+
+impl Generator {
+    /// Create the generator that best approximates `distribution` using weights that sum to
+    /// exactly `2^precision_bits`, i.e. the closest distribution that can be sampled with at most
+    /// `precision_bits` bits of entropy per pass and without any rejection. "Best" means the
+    /// approximation minimizes the given `divergence` among all such distributions, following
+    /// Saad et al., "Optimal Approximate Sampling from Discrete Probability Distributions" (2020).
+    /// # Panics
+    /// Will panic if `distribution` is invalid for `Generator::new`, if `2^precision_bits` does
+    /// not fit in a `usize`, or if fewer than two weights are non-zero in the optimum.
+    #[must_use]
+    pub fn optimal_approximation(
+        distribution: &[usize],
+        precision_bits: u32,
+        divergence: Divergence,
+    ) -> Approximation {
+        assert!(
+            precision_bits < usize::BITS,
+            "The precision must be less than the bit width of a `usize`."
+        );
+        let total = 1usize << precision_bits;
+        let sum = distribution
+            .iter()
+            .try_fold(0usize, |sum, &w| sum.checked_add(w))
+            .expect("The sum of the weights must fit in a `usize`.");
+        let target: Vec<_> = distribution
+            .iter()
+            .map(|&w| w as f64 / sum as f64)
+            .collect();
+
+        let weights = minimize_divergence(
+            &target,
+            largest_remainder(distribution, sum, total),
+            divergence,
+        );
+        Approximation {
+            max_bias: max_bias(distribution, sum, &weights, total),
+            kl_divergence: kl_divergence(target.iter().copied(), &weights, total),
+            generator: Generator::new(&weights),
+        }
+    }
+}
+
+impl Generator {
+    /// Create a generator from floating-point probabilities, e.g. the output of a statistical
+    /// model, using weights that sum to exactly `2^precision_bits`. The probabilities are first
+    /// normalized by their sum, so they need not sum to exactly one. Each is then scaled by
+    /// `2^precision_bits` and rounded down, and the shortfall is given one unit at a time to the
+    /// buckets with the largest remainders, preferring smaller indices on ties. This rounding
+    /// moves no bucket's probability by more than `2^-precision_bits`, up to floating-point error,
+    /// and the returned `max_bias` reports the error actually introduced.
+    /// # Panics
+    /// Will panic if a probability is negative or not finite, if every probability is zero, if
+    /// `2^precision_bits` does not fit in a `usize`, or if fewer than two weights are non-zero
+    /// after rounding.
+    #[must_use]
+    pub fn from_probabilities(probabilities: &[f64], precision_bits: u32) -> Approximation {
+        let (target, weights) = round_probabilities(probabilities, precision_bits);
+        approximation_of(&target, weights)
+    }
+
+    /// Create the generator that best approximates floating-point `probabilities` using weights
+    /// that sum to exactly `2^precision_bits`, i.e. a tree of at most `precision_bits` levels
+    /// that never rejects. The probabilities are normalized and rounded as by
+    /// `Generator::from_probabilities`, and units of weight are then moved between buckets until
+    /// the given `divergence` from the normalized probabilities is minimal among all such weights,
+    /// as by `Generator::optimal_approximation`. Unlike naive rounding, minimizing
+    /// `Divergence::KullbackLeibler` keeps every small probability sampleable whenever the
+    /// precision allows it.
+    /// # Panics
+    /// Will panic in the same cases as `Generator::from_probabilities`.
+    #[must_use]
+    pub fn optimal_from_probabilities(
+        probabilities: &[f64],
+        precision_bits: u32,
+        divergence: Divergence,
+    ) -> Approximation {
+        let (target, weights) = round_probabilities(probabilities, precision_bits);
+        let weights = minimize_divergence(&target, weights, divergence);
+        approximation_of(&target, weights)
+    }
+}
+
+/// Normalize `probabilities` by their sum, and round them to integer weights that sum to exactly
+/// `2^precision_bits` with the largest remainder method. Returns the normalized probabilities and
+/// the weights.
+fn round_probabilities(probabilities: &[f64], precision_bits: u32) -> (Vec<f64>, Vec<usize>) {
+    assert!(
+        precision_bits < usize::BITS,
+        "The precision must be less than the bit width of a `usize`."
+    );
+    assert!(
+        probabilities.iter().all(|p| p.is_finite() && *p >= 0.),
+        "Every probability must be non-negative and finite."
+    );
+    let sum: f64 = probabilities.iter().sum();
+    assert!(sum > 0., "At least one probability must be non-zero.");
+
+    let total = 1usize << precision_bits;
+    let scale = total as f64 / sum;
+    let (mut weights, remainders): (Vec<_>, Vec<_>) = probabilities
+        .iter()
+        .map(|&p| {
+            let scaled = p * scale;
+            (scaled.floor() as usize, scaled - scaled.floor())
+        })
+        .unzip();
+
+    // Floating-point error can leave the rounded weights a few units away from the total in
+    // either direction, so units are added to, or taken from, the buckets in remainder order.
+    let mut order: Vec<_> = (0..weights.len()).collect();
+    order.sort_by(|&i, &j| remainders[j].total_cmp(&remainders[i]).then(i.cmp(&j)));
+    let rounded = weights.iter().sum::<usize>();
+    for &i in order.iter().cycle().take(total.saturating_sub(rounded)) {
+        weights[i] += 1;
+    }
+    for _ in total..rounded {
+        let &i = order
+            .iter()
+            .rev()
+            .find(|&&i| weights[i] > 0)
+            .expect("The weights sum to more than zero.");
+        weights[i] -= 1;
+    }
+
+    let target = probabilities.iter().map(|&p| p / sum).collect();
+    (target, weights)
+}
+
+/// Build the generator for `weights`, which sum to a power of two, and measure its error from the
+/// normalized `target` probabilities.
+fn approximation_of(target: &[f64], weights: Vec<usize>) -> Approximation {
+    let total = weights.iter().sum::<usize>();
+    let max_bias = target
+        .iter()
+        .zip(&weights)
+        .map(|(&p, &w)| (p - w as f64 / total as f64).abs())
+        .fold(0., f64::max);
+    Approximation {
+        kl_divergence: kl_divergence(target.iter().copied(), &weights, total),
+        generator: Generator::new(&weights),
+        max_bias,
+    }
+}
+
+/// Improve the integer `weights` until no transfer of a single unit of weight between two buckets
+/// reduces the divergence from the `target` probabilities. Because the divergence is a sum of
+/// convex functions of each weight, such a local optimum is also the global optimum among all
+/// weights with the same total.
+pub(crate) fn minimize_divergence(
+    target: &[f64],
+    mut weights: Vec<usize>,
+    divergence: Divergence,
+) -> Vec<usize> {
+    let total = weights.iter().sum::<usize>() as f64;
+    let term = |i: usize, w: usize| divergence.term(target[i], w as f64 / total);
+
+    loop {
+        // The change in divergence from adding a unit to bucket `j`, and from removing one from `i`.
+        let (j, gain) = (0..weights.len())
+            .map(|j| (j, term(j, weights[j] + 1) - term(j, weights[j])))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .expect("The distribution is not empty.");
+        let Some((i, loss)) = (0..weights.len())
+            .filter(|&i| i != j && weights[i] > 0)
+            .map(|i| (i, term(i, weights[i] - 1) - term(i, weights[i])))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+        else {
+            return weights;
+        };
+
+        // Require a strict improvement beyond rounding error so that the search terminates.
+        // Moving weight onto a bucket whose divergence is infinite is always an improvement.
+        let change = gain + loss;
+        let improves =
+            change == f64::NEG_INFINITY || change < -f64::EPSILON * (gain.abs() + loss.abs());
+        if !improves {
+            return weights;
+        }
+        weights[i] -= 1;
+        weights[j] += 1;
+    }
+}
+
+/// Scale `distribution`, which sums to `sum`, to integer weights that sum to exactly `target`.
+/// Each weight is first rounded down, and the shortfall is given to the weights with the largest
+/// remainders.
+pub(crate) fn largest_remainder(distribution: &[usize], sum: usize, target: usize) -> Vec<usize> {
+    // The products fit in a `u128` because both factors fit in a `usize`.
+    let (mut weights, remainders): (Vec<_>, Vec<_>) = distribution
+        .iter()
+        .map(|&w| {
+            let scaled = w as u128 * target as u128;
+            ((scaled / sum as u128) as usize, scaled % sum as u128)
+        })
+        .unzip();
+
+    let shortfall = target - weights.iter().sum::<usize>();
+    let mut order: Vec<_> = (0..weights.len()).collect();
+    order.sort_by(|&i, &j| remainders[j].cmp(&remainders[i]).then(i.cmp(&j)));
+    for &i in &order[..shortfall] {
+        weights[i] += 1;
+    }
+    weights
+}
+
+/// The largest absolute difference between `w / sum` and `approximation / target` over all
+/// buckets, computed exactly before the final conversion to `f64`.
+pub(crate) fn max_bias(
+    distribution: &[usize],
+    sum: usize,
+    approximation: &[usize],
+    target: usize,
+) -> f64 {
+    let numerator = distribution
+        .iter()
+        .zip(approximation)
+        .map(|(&w, &a)| (w as u128 * target as u128).abs_diff(a as u128 * sum as u128))
+        .max()
+        .unwrap_or(0);
+    numerator as f64 / (sum as f64 * target as f64)
+}
+
+/// The Kullback-Leibler divergence, in bits, of the distribution with integer `approximation`
+/// weights summing to `total` from the `target` probabilities.
+pub(crate) fn kl_divergence(
+    target: impl Iterator<Item = f64>,
+    approximation: &[usize],
+    total: usize,
+) -> f64 {
+    target
+        .zip(approximation)
+        .filter(|&(p, _)| p > 0.)
+        .map(|(p, &w)| match w {
+            0 => f64::INFINITY,
+            w => p * (p * total as f64 / w as f64).log2(),
+        })
+        .sum()
+}
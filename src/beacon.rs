@@ -0,0 +1,158 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Coins driven by public randomness beacons, for drawings that anyone can audit.
+//!
+//! A randomness beacon (e.g. the NIST Randomness Beacon or drand) publishes a numbered sequence of
+//! unpredictable pulses. A `BeaconCoin` derives its entire stream of flips from one pulse, so a
+//! public drawing can announce in advance which round it will use, and afterwards anyone can
+//! re-run the draw from the published pulse to confirm the result.
+//!
+//! The pulse is expanded into an unbounded stream with SHA-256 in counter mode: block `i` is
+//! `SHA-256("fldr-beacon-v1" || round || len(randomness) || randomness || i)`, with integers
+//! encoded as little-endian `u64`s, and its bits are consumed most significant bit first.
+
+use alloc::vec::Vec;
+use sha2::{Digest, Sha256};
+
+use crate::{FairCoin, Generator};
+
+/// Domain separation for the hash expansion, so the stream cannot collide with other uses of the
+/// same pulse.
+const DOMAIN: &[u8] = b"fldr-beacon-v1";
+
+/// A coin whose flips are derived from a single beacon pulse.
+#[derive(Clone, Debug)]
+pub struct BeaconCoin {
+    round: u64,
+    randomness: Vec<u8>,
+    block: [u8; 32],
+    blocks_used: u64,
+    flips: usize,
+}
+
+impl BeaconCoin {
+    /// Create a coin from the pulse of the given beacon `round` with output `randomness`.
+    #[must_use]
+    pub fn new(round: u64, randomness: &[u8]) -> Self {
+        Self {
+            round,
+            randomness: randomness.to_vec(),
+            block: [0; 32],
+            blocks_used: 0,
+            flips: 0,
+        }
+    }
+
+    /// The beacon round this coin was derived from.
+    #[must_use]
+    pub fn round(&self) -> u64 {
+        self.round
+    }
+
+    /// The beacon output this coin was derived from.
+    #[must_use]
+    pub fn randomness(&self) -> &[u8] {
+        &self.randomness
+    }
+
+    /// The number of flips consumed so far.
+    #[must_use]
+    pub fn flips(&self) -> usize {
+        self.flips
+    }
+
+    /// Compute block `index` of the expanded stream.
+    fn expand(&self, index: u64) -> [u8; 32] {
+        Sha256::new()
+            .chain_update(DOMAIN)
+            .chain_update(self.round.to_le_bytes())
+            .chain_update((self.randomness.len() as u64).to_le_bytes())
+            .chain_update(&self.randomness)
+            .chain_update(index.to_le_bytes())
+            .finalize()
+            .into()
+    }
+}
+
+/// With the `zeroize` feature, clear the pulse and the current block of the expanded stream.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for BeaconCoin {
+    fn zeroize(&mut self) {
+        self.randomness.zeroize();
+        self.block.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for BeaconCoin {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for BeaconCoin {}
+
+impl FairCoin for BeaconCoin {
+    fn flip(&mut self) -> bool {
+        let bit = self.flips % 256;
+        if bit == 0 {
+            self.block = self.expand(self.blocks_used);
+            self.blocks_used += 1;
+        }
+        self.flips += 1;
+        self.block[bit / 8] >> (7 - bit % 8) & 1 > 0
+    }
+}
+
+/// The verifiable record of a single draw from a beacon pulse.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BeaconDraw {
+    /// The beacon round that was used.
+    pub round: u64,
+
+    /// The beacon output that was used.
+    pub randomness: Vec<u8>,
+
+    /// The sampled index.
+    pub index: usize,
+
+    /// The number of flips of the expanded stream that the draw consumed.
+    pub flips: usize,
+}
+
+impl Generator {
+    /// Draw a single index from the pulse of the given beacon `round`, returning a record that
+    /// anyone holding the same distribution can verify by drawing again.
+    #[must_use]
+    pub fn draw_from_beacon(&self, round: u64, randomness: &[u8]) -> BeaconDraw {
+        let mut coin = BeaconCoin::new(round, randomness);
+        let index = self.sample(&mut coin);
+        BeaconDraw {
+            round,
+            randomness: randomness.to_vec(),
+            index,
+            flips: coin.flips,
+        }
+    }
+}
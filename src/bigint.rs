@@ -0,0 +1,91 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Exact sampling from weights of arbitrary precision, such as combinatorial counts, using the
+//! crate [num-bigint](https://crates.io/crates/num-bigint).
+//!
+//! The DDG tree has one level per bit of the sum of the weights, so weights of thousands of bits
+//! are practical, while sampling itself only ever handles `usize` labels.
+
+use alloc::{vec, vec::Vec};
+use num_bigint::BigUint;
+
+use crate::{levels, Error, Generator, Layout};
+
+impl Generator {
+    /// Create a new DDG tree from weights of arbitrary precision.
+    /// # Panics
+    /// Will panic if `weights` has less than two non-zero weights, or if the level-label matrix
+    /// would not be addressable.
+    #[must_use]
+    pub fn from_big_weights(weights: &[BigUint]) -> Self {
+        Self::try_from_big_weights(weights).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Create a new DDG tree like `Generator::from_big_weights`, but surface invalid
+    /// distributions as an `Error` instead of panicking.
+    /// # Errors
+    /// Returns `Error::TooFewNonZeroWeights` if `weights` has less than two non-zero weights, or
+    /// `Error::TreeTooLarge` if the level-label matrix would not be addressable.
+    pub fn try_from_big_weights(weights: &[BigUint]) -> Result<Self, Error> {
+        if weights.iter().filter(|w| w.bits() > 0).count() < 2 {
+            return Err(Error::TooFewNonZeroWeights);
+        }
+        let sum: BigUint = weights.iter().sum();
+
+        // The depth is the ceiling of the base 2 logarithm of the sum, as for `Generator::new`.
+        let padded = sum.count_ones() > 1;
+        let depth =
+            usize::try_from(sum.bits() - u64::from(!padded)).map_err(|_| Error::TreeTooLarge)?;
+        let padding = (BigUint::from(1u8) << depth) - &sum;
+        let leaf_count = weights
+            .iter()
+            .chain([&padding])
+            .try_fold(0usize, |leaves, w| {
+                leaves.checked_add(usize::try_from(w.count_ones()).ok()?)
+            })
+            .ok_or(Error::TreeTooLarge)?;
+
+        let layout = Layout::try_from_depth(weights.len(), depth, padded, leaf_count)?;
+        Ok(Self::with_matrix(layout, |layout, matrix| {
+            layout.fill_bits(
+                |i, k| weights.get(i).unwrap_or(&padding).bit(k as u64),
+                matrix,
+            );
+        }))
+    }
+
+    /// Recover the weights of the input distribution from the DDG tree at arbitrary precision.
+    #[must_use]
+    pub fn big_weights(&self) -> Vec<BigUint> {
+        let depth = self.depth();
+        let mut weights = vec![BigUint::default(); self.bucket_count];
+        for (j, labels) in levels(&self.level_label_matrix).enumerate() {
+            for &label in labels {
+                if label < self.bucket_count {
+                    weights[label].set_bit((depth - j - 1) as u64, true);
+                }
+            }
+        }
+        weights
+    }
+}
@@ -0,0 +1,134 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Configurable, fallible construction of generators from untrusted input.
+
+use crate::{label::Label, Error, Generator, Layout};
+
+/// Builds a `Generator` while enforcing resource limits, so that services accepting user-supplied
+/// weights can reject pathological distributions before any memory is allocated for them.
+///
+/// ```
+/// use fast_loaded_dice_roller as fldr;
+///
+/// let builder = fldr::Generator::builder()
+///     .max_buckets(1_000)
+///     .max_matrix_bytes(1 << 20);
+/// assert!(builder.build(&[1, 2, 3]).is_ok());
+/// assert_eq!(
+///     builder.build(&[1; 2_000]).err(),
+///     Some(fldr::Error::TooManyBuckets)
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GeneratorBuilder {
+    max_buckets: Option<usize>,
+    max_depth: Option<usize>,
+    max_matrix_bytes: Option<usize>,
+}
+
+impl GeneratorBuilder {
+    /// Create a builder without any limits.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject distributions with more than `max_buckets` buckets, including zero weights.
+    #[must_use]
+    pub fn max_buckets(mut self, max_buckets: usize) -> Self {
+        self.max_buckets = Some(max_buckets);
+        self
+    }
+
+    /// Reject distributions whose DDG tree would have more than `max_depth` levels, i.e. whose sum
+    /// of weights exceeds `2^max_depth`.
+    #[must_use]
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Reject distributions whose level-label matrix would occupy more than `max_matrix_bytes`.
+    #[must_use]
+    pub fn max_matrix_bytes(mut self, max_matrix_bytes: usize) -> Self {
+        self.max_matrix_bytes = Some(max_matrix_bytes);
+        self
+    }
+
+    /// Validate `distribution` against the limits and build its generator.
+    /// # Errors
+    /// Returns an `Error` if `distribution` exceeds any of the configured limits, or if it is
+    /// invalid for `Generator::new`.
+    pub fn build(&self, distribution: &[usize]) -> Result<Generator, Error> {
+        self.build_compact(distribution)
+    }
+
+    /// Validate `distribution` against the limits and build its generator with labels stored as
+    /// `L`, e.g. `u16` for distributions with fewer than 65,535 buckets. The matrix is measured in
+    /// units of `L`, so narrower labels also admit larger trees under `max_matrix_bytes`.
+    ///
+    /// ```
+    /// use fast_loaded_dice_roller as fldr;
+    ///
+    /// let builder = fldr::Generator::builder();
+    /// let compact: fldr::Generator<u16> = builder.build_compact(&[1, 2, 3]).unwrap();
+    /// assert_eq!(compact.weights(), [1, 2, 3]);
+    /// assert_eq!(
+    ///     builder.build_compact::<u16>(&[1; 70_000]).err(),
+    ///     Some(fldr::Error::TooManyBuckets)
+    /// );
+    /// ```
+    /// # Errors
+    /// Returns `Error::TooManyBuckets` if a label of the tree would not fit in `L`, and otherwise
+    /// the same errors as `GeneratorBuilder::build`.
+    pub fn build_compact<L: Label>(&self, distribution: &[usize]) -> Result<Generator<L>, Error> {
+        // Check the cheapest limit before even reading the weights.
+        if self.max_buckets.is_some_and(|max| distribution.len() > max) {
+            return Err(Error::TooManyBuckets);
+        }
+
+        let layout = Layout::try_new(distribution)?;
+        if layout.adjusted_bucket_count > L::MAX_BUCKETS {
+            return Err(Error::TooManyBuckets);
+        }
+        if self.max_depth.is_some_and(|max| layout.depth > max) {
+            return Err(Error::TooDeep);
+        }
+        if let Some(max) = self.max_matrix_bytes {
+            let bytes = layout.matrix_len().checked_mul(core::mem::size_of::<L>());
+            if bytes.is_none_or(|bytes| bytes > max) {
+                return Err(Error::TooLarge);
+            }
+        }
+
+        Ok(Generator::from_layout(layout, distribution))
+    }
+}
+
+impl Generator {
+    /// Create a builder for constructing generators with resource limits.
+    #[must_use]
+    pub fn builder() -> GeneratorBuilder {
+        GeneratorBuilder::new()
+    }
+}
@@ -0,0 +1,140 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Weighted fault injection for chaos testing.
+//!
+//! A `FaultInjector` decides, once per operation, whether to let the operation through or to
+//! inject a fault. Fault rates are given as exact integer weights, so rare faults such as
+//! "1 in 100,000 responses is corrupted" are injected at exactly that rate rather than at a
+//! floating-point approximation of it. The injector is `Sync` and draws its flips from a shared
+//! lock-free stream, so a single instance can be shared by every thread of a service.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{
+    choice::Choice,
+    procgen::{mix, GOLDEN_GAMMA},
+    FairCoin,
+};
+
+/// The decision for a single operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Fault {
+    /// Let the operation through untouched.
+    None,
+
+    /// Delay the operation.
+    Latency,
+
+    /// Fail the operation with an error.
+    Error,
+
+    /// Let the operation through with corrupted data.
+    Corrupt,
+}
+
+impl Fault {
+    /// Every decision, in the order of their weights.
+    const ALL: [Self; 4] = [Self::None, Self::Latency, Self::Error, Self::Corrupt];
+}
+
+/// The relative weight of each `Fault`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FaultWeights {
+    /// The weight of letting the operation through.
+    pub none: usize,
+
+    /// The weight of delaying the operation.
+    pub latency: usize,
+
+    /// The weight of failing the operation.
+    pub error: usize,
+
+    /// The weight of corrupting the operation.
+    pub corrupt: usize,
+}
+
+/// Samples a `Fault` per operation. Safe to share between threads.
+pub struct FaultInjector {
+    choice: Choice,
+    state: AtomicU64,
+}
+
+impl FaultInjector {
+    /// Create an injector with the given fault weights, whose shared stream of flips starts from
+    /// `seed`.
+    /// # Panics
+    /// Will panic if every weight is zero, or if the sum of the weights overflows a `usize`.
+    #[must_use]
+    pub fn new(weights: FaultWeights, seed: u64) -> Self {
+        let FaultWeights {
+            none,
+            latency,
+            error,
+            corrupt,
+        } = weights;
+        Self {
+            choice: Choice::new(&[none, latency, error, corrupt])
+                .expect("At least one fault weight must be non-zero."),
+            state: AtomicU64::new(seed),
+        }
+    }
+
+    /// Decide the fault for one operation, using the injector's shared stream of flips.
+    pub fn decide(&self) -> Fault {
+        self.decide_with(&mut SharedCoin {
+            state: &self.state,
+            random_bits: 0,
+            bits_read: u64::BITS,
+        })
+    }
+
+    /// Decide the fault for one operation using a given `FairCoin`, e.g. a seeded coin for a
+    /// reproducible test run.
+    pub fn decide_with(&self, fair_coin: &mut impl FairCoin) -> Fault {
+        Fault::ALL[self.choice.sample(fair_coin)]
+    }
+}
+
+/// A coin that takes whole words from a SplitMix64 stream whose state is shared between threads.
+///
+/// Each word is claimed with a single atomic increment, so concurrent decisions never see the same
+/// bits and never block one another.
+struct SharedCoin<'a> {
+    state: &'a AtomicU64,
+    random_bits: u64,
+    bits_read: u32,
+}
+
+impl FairCoin for SharedCoin<'_> {
+    fn flip(&mut self) -> bool {
+        if self.bits_read == u64::BITS {
+            let state = self.state.fetch_add(GOLDEN_GAMMA, Ordering::Relaxed);
+            self.random_bits = mix(state.wrapping_add(GOLDEN_GAMMA));
+            self.bits_read = 0;
+        }
+        let bit = self.random_bits & 1 > 0;
+        self.random_bits >>= 1;
+        self.bits_read += 1;
+        bit
+    }
+}
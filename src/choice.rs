@@ -0,0 +1,53 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{FairCoin, Generator};
+
+/// A distribution in which a single outcome may have all of the weight, which `Generator` does
+/// not accept.
+#[derive(Clone)]
+pub(crate) enum Choice {
+    /// The only outcome with a non-zero weight.
+    Fixed(usize),
+
+    /// A distribution with at least two possible outcomes.
+    Random(Generator),
+}
+
+impl Choice {
+    /// Build a choice from its weights, or `None` if every weight is zero.
+    pub(crate) fn new(weights: &[usize]) -> Option<Self> {
+        let mut nonzero = weights.iter().enumerate().filter(|(_, &w)| w > 0);
+        match (nonzero.next(), nonzero.next()) {
+            (None, _) => None,
+            (Some((i, _)), None) => Some(Self::Fixed(i)),
+            _ => Some(Self::Random(Generator::new(weights))),
+        }
+    }
+
+    pub(crate) fn sample(&self, fair_coin: &mut impl FairCoin) -> usize {
+        match self {
+            Self::Fixed(i) => *i,
+            Self::Random(generator) => generator.sample(fair_coin),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Code generation for samplers embedded in static memory.
+//!
+//! Firmware and other constrained targets may not want to build a DDG tree at startup, or to have
+//! a heap at all. `static_generator_source` can be called from a build script to emit the packed
+//! level-label matrix for a fixed distribution as a `static` array, along with a
+//! `StaticGenerator` that samples from it with this crate's own sampling loop:
+//!
+//! ```no_run
+//! // build.rs
+//! use fast_loaded_dice_roller as fldr;
+//!
+//! let source = fldr::codegen::static_generator_source("LOOT", &[60, 25, 10, 5]);
+//! let out_dir = std::env::var("OUT_DIR").unwrap();
+//! std::fs::write(format!("{out_dir}/loot.rs"), source).unwrap();
+//! ```
+//!
+//! The crate then pulls in the sampler with `include!(concat!(env!("OUT_DIR"), "/loot.rs"));`
+//! and samples with `LOOT.sample(&mut coin)`.
+
+use alloc::{format, string::String, string::ToString, vec::Vec};
+use core::fmt::Write;
+
+use crate::{levels, sample_matrix, FairCoin, Generator};
+
+/// A sampler whose DDG tree lives in borrowed, typically `static`, memory.
+#[derive(Clone, Copy, Debug)]
+pub struct StaticGenerator<'a> {
+    bucket_count: usize,
+    level_label_matrix: &'a [usize],
+}
+
+impl<'a> StaticGenerator<'a> {
+    /// Wrap a packed level-label matrix emitted by `static_generator_source`.
+    /// # Panics
+    /// Will panic, at compile time when used in a `static`, if the matrix is not made of whole
+    /// levels, each a leaf count followed by that many labels. A matrix that was not produced by
+    /// this crate may still make sampling panic or fail to terminate, but never causes undefined
+    /// behavior.
+    #[must_use]
+    pub const fn from_raw_parts(bucket_count: usize, level_label_matrix: &'a [usize]) -> Self {
+        let mut row_start = 0;
+        while row_start < level_label_matrix.len() {
+            row_start = row_start.saturating_add(level_label_matrix[row_start].saturating_add(1));
+        }
+        assert!(
+            row_start == level_label_matrix.len(),
+            "The level-label matrix must be made of whole levels."
+        );
+        Self {
+            bucket_count,
+            level_label_matrix,
+        }
+    }
+
+    /// The number of buckets in the distribution.
+    #[must_use]
+    pub const fn bucket_count(&self) -> usize {
+        self.bucket_count
+    }
+
+    /// Sample a random index in the range `0..bucket_count` using a given `FairCoin`.
+    pub fn sample(&self, fair_coin: &mut impl FairCoin) -> usize {
+        sample_matrix(self.level_label_matrix, self.bucket_count, fair_coin)
+    }
+}
+
+impl Generator {
+    /// Borrow the DDG tree as a `StaticGenerator`.
+    #[must_use]
+    pub fn as_static(&self) -> StaticGenerator<'_> {
+        StaticGenerator::from_raw_parts(self.bucket_count, &self.level_label_matrix)
+    }
+}
+
+/// Emit Rust source declaring `static {name}: StaticGenerator` for `distribution`, backed by a
+/// `static {name}_MATRIX` array holding its level-label matrix.
+/// # Panics
+/// Will panic if `distribution` is invalid for `Generator::new`.
+#[must_use]
+pub fn static_generator_source(name: &str, distribution: &[usize]) -> String {
+    let generator = Generator::new(distribution);
+    let matrix = &generator.level_label_matrix;
+
+    let mut source = format!(
+        "// Generated by fast_loaded_dice_roller::codegen for the weights {distribution:?}.\n\
+         static {name}_MATRIX: [usize; {}] = [\n",
+        matrix.len()
+    );
+    for labels in levels(matrix) {
+        let row: Vec<_> = core::iter::once(&labels.len())
+            .chain(labels)
+            .map(usize::to_string)
+            .collect();
+        writeln!(source, "    {},", row.join(", ")).expect("Writing to a `String` cannot fail.");
+    }
+    write!(
+        source,
+        "];\n\
+         pub static {name}: fast_loaded_dice_roller::codegen::StaticGenerator<'static> =\n    \
+         fast_loaded_dice_roller::codegen::StaticGenerator::from_raw_parts({}, &{name}_MATRIX);\n",
+        generator.bucket_count
+    )
+    .expect("Writing to a `String` cannot fail.");
+    source
+}
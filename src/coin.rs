@@ -0,0 +1,462 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Adapters that build new `FairCoin`s out of existing ones.
+
+use alloc::vec::Vec;
+
+use crate::{Error, FairCoin};
+
+/// The order in which a coin consumes the bits of each block of random data.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BitOrder {
+    /// Consume the least significant bit first. This is the default.
+    #[default]
+    LsbFirst,
+
+    /// Consume the most significant bit first, for compatibility with implementations and recorded
+    /// test vectors that read bits in that order.
+    MsbFirst,
+}
+
+/// A coin whose flips are the XOR of the flips of two underlying coins.
+///
+/// The XOR of two independent bits is fair as long as either bit is fair, so combining a trusted
+/// coin with a second, independent source hedges against one of them being weak. More than two
+/// sources can be combined by nesting, e.g. `CombinedCoin::new(CombinedCoin::new(a, b), c)`.
+pub struct CombinedCoin<A: FairCoin, B: FairCoin> {
+    first: A,
+    second: B,
+}
+
+impl<A: FairCoin, B: FairCoin> CombinedCoin<A, B> {
+    /// Create a new coin that XORs the flips of `first` and `second`.
+    #[must_use]
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Consume the combined coin and return the underlying coins.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A: FairCoin, B: FairCoin> FairCoin for CombinedCoin<A, B> {
+    fn flip(&mut self) -> bool {
+        self.first.flip() ^ self.second.flip()
+    }
+}
+
+/// A coin whose flips are the results of calling a closure, so that experiments and tests can
+/// script a coin without defining a type for it.
+///
+/// This is a newtype rather than an implementation of `FairCoin` for every `FnMut() -> bool`,
+/// which would overlap with implementing `FairCoin` for references to and boxes of coins.
+pub struct FnCoin<F: FnMut() -> bool>(F);
+
+impl<F: FnMut() -> bool> FnCoin<F> {
+    /// Create a coin that calls `flip` for each flip.
+    #[must_use]
+    pub fn new(flip: F) -> Self {
+        Self(flip)
+    }
+
+    /// Consume the coin and return the closure.
+    pub fn into_inner(self) -> F {
+        self.0
+    }
+}
+
+impl<F: FnMut() -> bool> FairCoin for FnCoin<F> {
+    fn flip(&mut self) -> bool {
+        (self.0)()
+    }
+}
+
+/// A coin whose flips are read from an iterator, e.g. to replay a precomputed tape of flips in a
+/// regression test or an entropy log read from a file.
+///
+/// A tape cannot be extended once it runs out, so a flip panics when the iterator is exhausted,
+/// reporting how many flips were read. A tape of bytes can be read bit by bit with
+/// `bytes.flat_map(|b| (0..8).map(move |i| b >> i & 1 == 1))`.
+pub struct IterCoin<I: Iterator<Item = bool>> {
+    flips: I,
+    read: u64,
+}
+
+impl<I: Iterator<Item = bool>> IterCoin<I> {
+    /// Create a coin that reads its flips from `flips` in order.
+    #[must_use]
+    pub fn new(flips: impl IntoIterator<IntoIter = I>) -> Self {
+        Self {
+            flips: flips.into_iter(),
+            read: 0,
+        }
+    }
+
+    /// The number of flips read from the iterator so far.
+    #[must_use]
+    pub fn flips(&self) -> u64 {
+        self.read
+    }
+
+    /// Consume the coin and return the iterator of the flips that have not been read.
+    pub fn into_inner(self) -> I {
+        self.flips
+    }
+}
+
+/// Read the next flip from the iterator.
+/// A flip panics if the iterator is exhausted.
+impl<I: Iterator<Item = bool>> FairCoin for IterCoin<I> {
+    fn flip(&mut self) -> bool {
+        let Some(bit) = self.flips.next() else {
+            panic!(
+                "The iterator of flips was exhausted after {} flips.",
+                self.read
+            );
+        };
+        self.read += 1;
+        bit
+    }
+}
+
+/// Wraps a coin to count the number of flips taken from it, e.g. to monitor the entropy consumed
+/// from a hardware source.
+pub struct CountingCoin<'a, C: FairCoin> {
+    coin: &'a mut C,
+    flips: u64,
+}
+
+impl<'a, C: FairCoin> CountingCoin<'a, C> {
+    /// Start counting the flips of `coin`.
+    #[must_use]
+    pub fn new(coin: &'a mut C) -> Self {
+        Self { coin, flips: 0 }
+    }
+
+    /// The number of flips taken so far.
+    #[must_use]
+    pub fn flips(&self) -> u64 {
+        self.flips
+    }
+}
+
+impl<C: FairCoin> FairCoin for CountingCoin<'_, C> {
+    fn flip(&mut self) -> bool {
+        self.flips += 1;
+        self.coin.flip()
+    }
+}
+
+/// The health test that a monitored coin failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HealthFailure {
+    /// The coin produced too many identical flips in a row, so it appears to be stuck.
+    RepetitionCount,
+
+    /// One side came up too often within a window of flips, so the coin appears to be biased.
+    AdaptiveProportion,
+}
+
+/// A coin that passes through the flips of an underlying coin while running the continuous
+/// health tests of NIST SP 800-90B (section 4.4) over them.
+///
+/// The repetition count test fails when a single value repeats `repetition_cutoff` times in a row,
+/// and the adaptive proportion test fails when the first flip of a window of 1024 flips recurs
+/// `proportion_cutoff` or more times within that window. The default cutoffs assume a full bit of
+/// entropy per flip and a false positive rate of 2^-20 per test.
+///
+/// A failure does not interrupt sampling; it is latched until `reset` and can be polled with
+/// `failure` or `check` between samples.
+pub struct HealthCheckedCoin<C: FairCoin> {
+    coin: C,
+    repetition_cutoff: usize,
+    proportion_cutoff: usize,
+    last: bool,
+    run: usize,
+    reference: bool,
+    window_len: usize,
+    window_matches: usize,
+    failure: Option<HealthFailure>,
+}
+
+impl<C: FairCoin> HealthCheckedCoin<C> {
+    /// The number of flips in each window of the adaptive proportion test.
+    pub const WINDOW: usize = 1024;
+
+    /// Monitor `coin` with the default cutoffs.
+    #[must_use]
+    pub fn new(coin: C) -> Self {
+        Self::with_cutoffs(coin, 21, 589)
+    }
+
+    /// Monitor `coin` with custom cutoffs, e.g. ones derived from a lower entropy estimate.
+    #[must_use]
+    pub fn with_cutoffs(coin: C, repetition_cutoff: usize, proportion_cutoff: usize) -> Self {
+        Self {
+            coin,
+            repetition_cutoff,
+            proportion_cutoff,
+            last: false,
+            run: 0,
+            reference: false,
+            window_len: 0,
+            window_matches: 0,
+            failure: None,
+        }
+    }
+
+    /// The first health test failure since creation or the last `reset`, if any.
+    #[must_use]
+    pub fn failure(&self) -> Option<HealthFailure> {
+        self.failure
+    }
+
+    /// Return an error if either health test has failed since creation or the last `reset`.
+    pub fn check(&self) -> Result<(), Error> {
+        match self.failure {
+            Some(_) => Err(Error::UnhealthyCoin),
+            None => Ok(()),
+        }
+    }
+
+    /// Clear a latched failure and restart both tests.
+    pub fn reset(&mut self) {
+        self.run = 0;
+        self.window_len = 0;
+        self.window_matches = 0;
+        self.failure = None;
+    }
+
+    /// Consume the monitor and return the underlying coin.
+    pub fn into_inner(self) -> C {
+        self.coin
+    }
+}
+
+impl<C: FairCoin> FairCoin for HealthCheckedCoin<C> {
+    fn flip(&mut self) -> bool {
+        let bit = self.coin.flip();
+
+        if self.run > 0 && bit == self.last {
+            self.run += 1;
+        } else {
+            self.last = bit;
+            self.run = 1;
+        }
+        if self.run >= self.repetition_cutoff {
+            self.failure.get_or_insert(HealthFailure::RepetitionCount);
+        }
+
+        if self.window_len == 0 {
+            self.reference = bit;
+            self.window_matches = 0;
+        }
+        self.window_len = (self.window_len + 1) % Self::WINDOW;
+        if bit == self.reference {
+            self.window_matches += 1;
+            if self.window_matches >= self.proportion_cutoff {
+                self.failure
+                    .get_or_insert(HealthFailure::AdaptiveProportion);
+            }
+        }
+
+        bit
+    }
+}
+
+/// The number of raw flips that a `DebiasedCoin` extracts from at a time when iterating.
+const DEBIASED_COIN_BLOCK: usize = 256;
+
+/// A coin that turns the flips of a biased source into fair flips by von Neumann extraction.
+///
+/// The source must be a sequence of independent flips that all land heads with the same, unknown
+/// probability, as is typical of raw hardware noise. Von Neumann extraction reads the source in
+/// pairs, returns the first flip of each unequal pair, and discards equal pairs, so at least four
+/// source flips are used per fair flip. Iterated extraction (Peres, 1992) recursively extracts
+/// further fair flips from the discarded information, the XOR of each pair and the value of each
+/// equal pair, approaching the entropy of the source as the number of iterations grows.
+///
+/// A flip never returns if the source is stuck on one side, so an untrusted source should be
+/// monitored, e.g. with a `HealthCheckedCoin`.
+pub struct DebiasedCoin<C: FairCoin> {
+    coin: C,
+    iterations: usize,
+    extracted: Vec<bool>,
+}
+
+impl<C: FairCoin> DebiasedCoin<C> {
+    /// Extract fair flips from `coin` by plain von Neumann extraction, one pair at a time.
+    #[must_use]
+    pub fn new(coin: C) -> Self {
+        Self::iterated(coin, 0)
+    }
+
+    /// Extract fair flips from `coin` with the given number of iterations of Peres' procedure,
+    /// where zero is plain von Neumann extraction. When iterating, the source is read in blocks of
+    /// 256 flips, and the fair flips extracted from a block are buffered.
+    #[must_use]
+    pub fn iterated(coin: C, iterations: usize) -> Self {
+        Self {
+            coin,
+            iterations,
+            extracted: Vec::new(),
+        }
+    }
+
+    /// The number of iterations of extraction applied to the source.
+    #[must_use]
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Consume the extractor and return the underlying coin, discarding any buffered flips.
+    pub fn into_inner(self) -> C {
+        self.coin
+    }
+}
+
+/// Append the flips extracted from `flips` by the given number of iterations of Peres' procedure.
+/// The extracted flips are independent and fair given how many there are, so the order in which
+/// the recursive calls append them does not matter.
+fn extract(flips: &[bool], iterations: usize, extracted: &mut Vec<bool>) {
+    let mut xors = Vec::new();
+    let mut equals = Vec::new();
+    for pair in flips.chunks_exact(2) {
+        if pair[0] == pair[1] {
+            equals.push(pair[0]);
+        } else {
+            extracted.push(pair[0]);
+        }
+        xors.push(pair[0] ^ pair[1]);
+    }
+    if iterations > 0 {
+        extract(&xors, iterations - 1, extracted);
+        extract(&equals, iterations - 1, extracted);
+    }
+}
+
+impl<C: FairCoin> FairCoin for DebiasedCoin<C> {
+    fn flip(&mut self) -> bool {
+        loop {
+            if let Some(bit) = self.extracted.pop() {
+                return bit;
+            }
+
+            if self.iterations == 0 {
+                let (first, second) = (self.coin.flip(), self.coin.flip());
+                if first != second {
+                    return first;
+                }
+            } else {
+                let block: Vec<bool> = (0..DEBIASED_COIN_BLOCK).map(|_| self.coin.flip()).collect();
+                extract(&block, self.iterations, &mut self.extracted);
+            }
+        }
+    }
+}
+
+/// The number of bytes of operating system entropy an `OsCoin` buffers at a time.
+#[cfg(feature = "getrandom")]
+const OS_COIN_BUFFER: usize = 64;
+
+/// A coin whose flips are drawn directly from the operating system's entropy source through the
+/// crate [getrandom](https://crates.io/crates/getrandom), bypassing any userspace PRNG.
+///
+/// Entropy is requested 64 bytes at a time, so that sampling does not make a system
+/// call per flip. With the `zeroize` feature, the buffered bits are cleared when the coin is
+/// dropped.
+#[cfg(feature = "getrandom")]
+pub struct OsCoin {
+    buffer: [u8; OS_COIN_BUFFER],
+    bits_read: usize,
+}
+
+#[cfg(feature = "getrandom")]
+impl OsCoin {
+    /// Create a coin and fill its buffer from the operating system.
+    /// # Panics
+    /// Will panic if the operating system fails to provide entropy.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::try_new()
+            .unwrap_or_else(|e| panic!("The operating system failed to provide entropy: {e}"))
+    }
+
+    /// Create a coin and fill its buffer from the operating system.
+    /// # Errors
+    /// Returns the error of `getrandom` if the operating system fails to provide entropy.
+    pub fn try_new() -> Result<Self, getrandom::Error> {
+        let mut buffer = [0; OS_COIN_BUFFER];
+        getrandom::getrandom(&mut buffer)?;
+        Ok(Self {
+            buffer,
+            bits_read: 0,
+        })
+    }
+}
+
+#[cfg(feature = "getrandom")]
+impl Default for OsCoin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Flip the buffered bits in order, refilling the buffer from the operating system when every bit
+/// has been used. A flip panics if the operating system fails to provide entropy for a refill.
+#[cfg(feature = "getrandom")]
+impl FairCoin for OsCoin {
+    fn flip(&mut self) -> bool {
+        if self.bits_read == 8 * OS_COIN_BUFFER {
+            getrandom::getrandom(&mut self.buffer)
+                .unwrap_or_else(|e| panic!("The operating system failed to provide entropy: {e}"));
+            self.bits_read = 0;
+        }
+        let bit = self.buffer[self.bits_read / 8] >> (self.bits_read % 8) & 1 == 1;
+        self.bits_read += 1;
+        bit
+    }
+}
+
+/// With the `zeroize` feature, clear the buffered entropy.
+#[cfg(all(feature = "getrandom", feature = "zeroize"))]
+impl zeroize::Zeroize for OsCoin {
+    fn zeroize(&mut self) {
+        self.buffer.zeroize();
+        // Mark the buffer as consumed so that the next flip draws fresh entropy.
+        self.bits_read = 8 * OS_COIN_BUFFER;
+    }
+}
+
+#[cfg(all(feature = "getrandom", feature = "zeroize"))]
+impl Drop for OsCoin {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(all(feature = "getrandom", feature = "zeroize"))]
+impl zeroize::ZeroizeOnDrop for OsCoin {}
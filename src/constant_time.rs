@@ -0,0 +1,178 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Sampling in constant time, for cryptographic uses such as the noise of lattice-based schemes.
+//!
+//! `Generator::sample` walks the DDG tree only as deep as the sampled leaf and restarts from the
+//! root after a rejection, so both the number of flips and the memory accessed depend on the
+//! label drawn. A `ConstantTimeSampler` instead walks every level of the tree on each of a fixed
+//! number of passes, reading every label of every level and selecting the result with bit masks
+//! rather than branches. The flips consumed and the memory accessed are then the same for every
+//! sample. Rust makes no guarantee that the compiler preserves this, so the masks are passed
+//! through `core::hint::black_box` as a best effort and generated code should still be audited.
+
+use alloc::vec::Vec;
+
+use rand_core::{CryptoRng, RngCore};
+
+use crate::{label::Label, levels, rand_core::RngCoin, FairCoin, Generator};
+
+/// Samples a generator's distribution in constant time using a cryptographically secure RNG.
+///
+/// Each sample takes exactly `passes` passes from the root, each of which flips the coin
+/// `depth` times. A pass is rejected with probability `padding_weight / 2^depth`, and the sample
+/// fails only if every pass is rejected, so a generator without padding never fails and otherwise
+/// the number of passes trades time for a lower probability of failure.
+#[derive(Clone, Debug)]
+pub struct ConstantTimeSampler {
+    bucket_count: usize,
+    levels: Vec<Vec<usize>>,
+    passes: usize,
+}
+
+impl ConstantTimeSampler {
+    /// Create a sampler that takes exactly `passes` passes through the DDG tree of `generator`
+    /// on every sample.
+    /// # Panics
+    /// Will panic if `passes` is zero.
+    #[must_use]
+    pub fn new<L: Label>(generator: &Generator<L>, passes: usize) -> Self {
+        assert!(passes > 0, "A sample must take at least one pass.");
+        Self {
+            bucket_count: generator.bucket_count,
+            levels: levels(&generator.level_label_matrix)
+                .map(|labels| labels.iter().map(|label| label.to_usize()).collect())
+                .collect(),
+            passes,
+        }
+    }
+
+    /// Create a sampler that takes the fewest passes for a sample to fail with probability at
+    /// most `2^-security_bits`.
+    #[must_use]
+    pub fn with_security_bits<L: Label>(generator: &Generator<L>, security_bits: u32) -> Self {
+        // Track the probability that every pass so far was rejected as `mantissa * 2^-exponent`,
+        // with the mantissa in `(1/2, 1]`, since the probability soon underflows an `f64` and
+        // `no_std` has no logarithms. The probability is then at most `2^-exponent`, but more than
+        // `2^-(exponent + 1)`, so the first pass count whose exponent reaches `security_bits` is
+        // the fewest that suffice.
+        let normalize = |mut mantissa: f64, mut exponent: i64| {
+            while mantissa > 1. {
+                mantissa /= 2.;
+                exponent -= 1;
+            }
+            while mantissa <= 0.5 {
+                mantissa *= 2.;
+                exponent += 1;
+            }
+            (mantissa, exponent)
+        };
+
+        // A pass is rejected with probability `padding / 2^depth`, which every generator keeps
+        // below one half, so the exponent grows by at least one with each pass.
+        let padding = generator.padding_weight();
+        let mut passes = 1;
+        if padding > 0 {
+            let (rejection, rejection_exponent) =
+                normalize(padding as f64, generator.depth() as i64);
+            let (mut mantissa, mut exponent) = (rejection, rejection_exponent);
+            while exponent < i64::from(security_bits) {
+                (mantissa, exponent) =
+                    normalize(mantissa * rejection, exponent + rejection_exponent);
+                passes += 1;
+            }
+        }
+        Self::new(generator, passes)
+    }
+
+    /// The number of passes through the DDG tree taken by every sample.
+    #[must_use]
+    pub fn passes(&self) -> usize {
+        self.passes
+    }
+
+    /// The number of coin flips consumed by every sample.
+    #[must_use]
+    pub fn flips_per_sample(&self) -> usize {
+        self.passes * self.levels.len()
+    }
+
+    /// Sample a random item in constant time, or `None` if every pass was rejected.
+    ///
+    /// The coin's buffer is refilled after a fixed number of flips, so its timing also does not
+    /// depend on the label drawn.
+    pub fn sample<R: RngCore + CryptoRng>(&self, fair_coin: &mut RngCoin<R>) -> Option<usize> {
+        let mut found = 0;
+        let mut result = 0;
+        for _ in 0..self.passes {
+            let label = self.pass(fair_coin);
+            let accepted = black_box(less_than(label, self.bucket_count) & !found & 1);
+            result |= mask(accepted) & label;
+            found |= accepted;
+        }
+        (found == 1).then_some(result)
+    }
+
+    /// Walk every level of the DDG tree once and return the label of the leaf reached, which may
+    /// be the padding label. Every path from the root reaches a leaf within `depth` flips.
+    fn pass(&self, fair_coin: &mut impl FairCoin) -> usize {
+        let mut done = 0;
+        let mut label = 0;
+        let mut label_index = 0;
+        for labels in &self.levels {
+            label_index = (label_index << 1) | usize::from(fair_coin.flip());
+            let leaf_count = labels.len();
+            let hit = black_box(less_than(label_index, leaf_count) & !done & 1);
+
+            // Read every label of the level so that the index reached is not revealed.
+            for (i, &candidate) in labels.iter().enumerate() {
+                label |= mask(hit & equal(i, label_index)) & candidate;
+            }
+            done |= hit;
+
+            // Restart from the root after a leaf, otherwise wrap the index by the leaf count.
+            label_index = !mask(hit) & label_index.wrapping_sub(leaf_count);
+        }
+        label
+    }
+}
+
+/// Hide a value from the optimizer so that it does not turn mask arithmetic back into branches.
+fn black_box(x: usize) -> usize {
+    core::hint::black_box(x)
+}
+
+/// All ones if `bit` is one, or all zeros if it is zero.
+fn mask(bit: usize) -> usize {
+    0usize.wrapping_sub(bit)
+}
+
+/// One if `x < y`, otherwise zero, without branching.
+fn less_than(x: usize, y: usize) -> usize {
+    (x ^ ((x ^ y) | (x.wrapping_sub(y) ^ y))) >> (usize::BITS - 1)
+}
+
+/// One if `x == y`, otherwise zero, without branching.
+fn equal(x: usize, y: usize) -> usize {
+    let diff = x ^ y;
+    1 ^ ((diff | diff.wrapping_neg()) >> (usize::BITS - 1))
+}
@@ -0,0 +1,122 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Fallible conversions into a `Generator` from the containers callers commonly hold weights in.
+//!
+//! Integer weights are accepted at their own width, as by `Generator::try_from_weights`. Durations
+//! fail with `Error::WeightSumOverflow` if a length in nanoseconds does not fit in a `usize`. Every
+//! conversion otherwise fails with the same errors as `Generator::new` would panic with.
+
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use crate::{Error, Generator};
+
+impl Generator {
+    /// Build a generator from weights in fixed units, checking that they sum to exactly `total`.
+    fn from_fixed_units(weights: &[u32], total: usize) -> Result<Self, Error> {
+        let actual = weights
+            .iter()
+            .try_fold(0usize, |sum, &w| sum.checked_add(usize::try_from(w).ok()?))
+            .ok_or(Error::WeightSumOverflow)?;
+        if actual != total {
+            return Err(Error::WrongTotal {
+                expected: total,
+                actual,
+            });
+        }
+        Self::try_from(weights)
+    }
+
+    /// Build a generator from drop rates given as whole percentages, which must sum to 100.
+    ///
+    /// # Errors
+    /// Returns [`Error::WrongTotal`] if the percentages do not sum to exactly 100, or
+    /// [`Error::TooFewNonZeroWeights`] if fewer than two of them are non-zero.
+    pub fn from_percentages(percentages: &[u32]) -> Result<Self, Error> {
+        Self::from_fixed_units(percentages, 100)
+    }
+
+    /// Build a generator from drop rates given in basis points (hundredths of a percent), which
+    /// must sum to 10,000.
+    ///
+    /// # Errors
+    /// Returns [`Error::WrongTotal`] if the basis points do not sum to exactly 10,000, or
+    /// [`Error::TooFewNonZeroWeights`] if fewer than two of them are non-zero.
+    pub fn from_basis_points(basis_points: &[u32]) -> Result<Self, Error> {
+        Self::from_fixed_units(basis_points, 10_000)
+    }
+}
+
+impl TryFrom<&[usize]> for Generator {
+    type Error = Error;
+
+    fn try_from(distribution: &[usize]) -> Result<Self, Error> {
+        Self::try_new(distribution)
+    }
+}
+
+impl TryFrom<Vec<usize>> for Generator {
+    type Error = Error;
+
+    fn try_from(distribution: Vec<usize>) -> Result<Self, Error> {
+        Self::try_from(distribution.as_slice())
+    }
+}
+
+impl<const N: usize> TryFrom<[usize; N]> for Generator {
+    type Error = Error;
+
+    fn try_from(distribution: [usize; N]) -> Result<Self, Error> {
+        Self::try_from(distribution.as_slice())
+    }
+}
+
+impl TryFrom<&[u32]> for Generator {
+    type Error = Error;
+
+    fn try_from(distribution: &[u32]) -> Result<Self, Error> {
+        Self::try_from_weights(distribution)
+    }
+}
+
+impl TryFrom<&[u64]> for Generator {
+    type Error = Error;
+
+    fn try_from(distribution: &[u64]) -> Result<Self, Error> {
+        Self::try_from_weights(distribution)
+    }
+}
+
+/// Each duration is weighted by its length in nanoseconds.
+impl TryFrom<&[Duration]> for Generator {
+    type Error = Error;
+
+    fn try_from(durations: &[Duration]) -> Result<Self, Error> {
+        let nanos = durations
+            .iter()
+            .map(|d| usize::try_from(d.as_nanos()).ok())
+            .collect::<Option<Vec<_>>>()
+            .ok_or(Error::WeightSumOverflow)?;
+        Self::try_new(&nanos)
+    }
+}
@@ -0,0 +1,117 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! A truncated discrete Gaussian, the canonical noise distribution of lattice cryptography.
+//!
+//! The discrete Gaussian with parameter `sigma` and center `c` assigns each integer `x` a mass
+//! proportional to `exp(-(x - c)^2 / (2 sigma^2))`. Its support is unbounded, so it is cut to the
+//! integers within `tail_cut * sigma` of the center, the masses are normalized over exactly that
+//! support, and each is rounded to `precision_bits` bits as in `distributions`. The generator then
+//! samples exactly from those integer weights, so the statistical distance from the ideal
+//! distribution is bounded by the tail beyond the cut plus the rounding of each mass.
+
+use core::ops::RangeInclusive;
+
+use crate::{distributions::quantize, FairCoin, Generator};
+
+/// A sampler of integers from a truncated, quantized discrete Gaussian.
+#[derive(Clone, Debug)]
+pub struct DiscreteGaussian {
+    generator: Generator,
+    min: i64,
+}
+
+impl DiscreteGaussian {
+    /// Create a sampler of the discrete Gaussian with parameter `sigma` centered at `center`,
+    /// truncated to the integers `x` with `|x - center| <= tail_cut * sigma`.
+    ///
+    /// Integers whose masses round to zero at the requested precision are dropped from the ends
+    /// of the support, so they are never sampled.
+    /// # Panics
+    /// Will panic if `sigma` or `tail_cut` is not positive and finite, if `center` is not finite,
+    /// if the truncated support does not fit in an `i64`, if `precision_bits` is too large for a
+    /// `usize`, or if fewer than two integers have a non-zero weight at the requested precision.
+    #[must_use]
+    pub fn new(sigma: f64, center: f64, tail_cut: f64, precision_bits: u32) -> Self {
+        assert!(
+            sigma > 0. && sigma.is_finite(),
+            "The parameter sigma must be positive and finite."
+        );
+        assert!(center.is_finite(), "The center must be finite.");
+        assert!(
+            tail_cut > 0. && tail_cut.is_finite(),
+            "The tail cut must be positive and finite."
+        );
+
+        // Round the bounds inwards, so that no integer beyond the cut is included.
+        let radius = tail_cut * sigma;
+        let (low, high) = ((center - radius).ceil(), (center + radius).floor());
+        assert!(
+            low >= i64::MIN as f64 && high < i64::MAX as f64,
+            "The truncated support must fit in an `i64`."
+        );
+        let (low, high) = (low as i64, high as i64);
+
+        // Evaluate the unnormalized masses relative to the center, then normalize them over the
+        // truncated support rather than over all of the integers.
+        let two_variance = 2. * sigma * sigma;
+        let rho: Vec<f64> = (low..=high)
+            .map(|x| {
+                let offset = x as f64 - center;
+                (-offset * offset / two_variance).exp()
+            })
+            .collect();
+        let total: f64 = rho.iter().sum();
+        let mut weights = quantize(rho.iter().map(|r| r / total), precision_bits);
+
+        // Drop the integers at either end whose masses round to zero.
+        let leading = weights.iter().take_while(|&&w| w == 0).count();
+        weights.drain(..leading);
+        while weights.last() == Some(&0) {
+            weights.pop();
+        }
+
+        Self {
+            generator: Generator::new(&weights),
+            min: low + leading as i64,
+        }
+    }
+
+    /// The integers that may be sampled, i.e. those with a non-zero weight after truncation and
+    /// rounding.
+    #[must_use]
+    pub fn support(&self) -> RangeInclusive<i64> {
+        self.min..=self.min + (self.generator.bucket_count - 1) as i64
+    }
+
+    /// The generator over the support, whose bucket `i` is the integer `support().start() + i`,
+    /// e.g. to sample in constant time with `constant_time::ConstantTimeSampler`.
+    #[must_use]
+    pub fn generator(&self) -> &Generator {
+        &self.generator
+    }
+
+    /// Sample a random integer using a given `FairCoin`.
+    pub fn sample(&self, fair_coin: &mut impl FairCoin) -> i64 {
+        self.min + self.generator.sample(fair_coin) as i64
+    }
+}
@@ -0,0 +1,113 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Distances between the exact distribution of a `Generator` and another distribution, for
+//! validating samplers and detecting drift between a configured distribution and observed data.
+//!
+//! Other distributions are given as a probability mass function over the same buckets, or as an
+//! empirical histogram of counts, which can be converted with `empirical`.
+
+use crate::Generator;
+
+/// The exact probability of each bucket of `generator`.
+/// # Panics
+/// Will panic if `other_len`, the length of the distribution being compared against, is not the
+/// number of buckets of `generator`.
+fn exact_pmf(generator: &Generator, other_len: usize) -> Vec<f64> {
+    let weights = generator.weights();
+    assert_eq!(
+        weights.len(),
+        other_len,
+        "The distributions must have the same number of buckets."
+    );
+    let sum = weights.iter().sum::<usize>() as f64;
+    weights.into_iter().map(|w| w as f64 / sum).collect()
+}
+
+/// Normalize a histogram of counts into an empirical probability mass function.
+/// # Panics
+/// Will panic if every count is zero.
+#[must_use]
+pub fn empirical(histogram: &[usize]) -> Vec<f64> {
+    let total = histogram.iter().sum::<usize>();
+    assert!(total > 0, "The histogram must contain at least one count.");
+    histogram.iter().map(|&c| c as f64 / total as f64).collect()
+}
+
+/// The total variation distance between the distribution of `generator` and `pmf`, i.e. the
+/// largest difference in probability that the two distributions assign to any event.
+/// # Panics
+/// Will panic if `pmf` does not have one entry per bucket of `generator`.
+#[must_use]
+pub fn total_variation(generator: &Generator, pmf: &[f64]) -> f64 {
+    exact_pmf(generator, pmf.len())
+        .iter()
+        .zip(pmf)
+        .map(|(p, q)| (p - q).abs())
+        .sum::<f64>()
+        / 2.
+}
+
+/// The Kullback-Leibler divergence, in bits, of `pmf` from the distribution of `generator`.
+/// The result is infinite if `pmf` assigns zero probability to a bucket the generator can sample.
+/// # Panics
+/// Will panic if `pmf` does not have one entry per bucket of `generator`.
+#[must_use]
+pub fn kl_divergence(generator: &Generator, pmf: &[f64]) -> f64 {
+    exact_pmf(generator, pmf.len())
+        .iter()
+        .zip(pmf)
+        .filter(|(&p, _)| p > 0.)
+        .map(|(&p, &q)| {
+            if q > 0. {
+                p * (p / q).log2()
+            } else {
+                f64::INFINITY
+            }
+        })
+        .sum()
+}
+
+/// Pearson's chi-square statistic for an observed `histogram` against the counts expected from
+/// the distribution of `generator`. The result is infinite if a bucket the generator can never
+/// sample has a non-zero count.
+/// # Panics
+/// Will panic if `histogram` does not have one entry per bucket of `generator`.
+#[must_use]
+pub fn chi_square(generator: &Generator, histogram: &[usize]) -> f64 {
+    let total = histogram.iter().sum::<usize>() as f64;
+    exact_pmf(generator, histogram.len())
+        .iter()
+        .zip(histogram)
+        .map(|(&p, &observed)| {
+            let expected = p * total;
+            let observed = observed as f64;
+            if expected > 0. {
+                (observed - expected).powi(2) / expected
+            } else if observed > 0. {
+                f64::INFINITY
+            } else {
+                0.
+            }
+        })
+        .sum()
+}
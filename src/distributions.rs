@@ -0,0 +1,163 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Builders for generators that sample from classic discrete distributions.
+//!
+//! Each builder evaluates the probability mass function in floating point, scales it by
+//! `2^precision_bits`, and rounds every mass to the nearest integer weight. The resulting
+//! `Generator` then samples exactly from those integer weights, so the only error is the rounding
+//! of each probability to `precision_bits` bits (plus the roughly 53 bits of precision of an
+//! `f64`). Sampled indices are the values of the random variable, e.g. the number of failures,
+//! except that `binomial` also returns the value of its first index.
+
+use crate::Generator;
+
+/// Scale the masses by `2^precision_bits` and round them to integer weights.
+pub(crate) fn quantize(pmf: impl Iterator<Item = f64>, precision_bits: u32) -> Vec<usize> {
+    // Leave headroom so that the rounded weights cannot overflow their sum.
+    assert!(
+        precision_bits <= usize::BITS - 2,
+        "The precision must leave two bits of headroom in a `usize`."
+    );
+    let scale = f64::from(precision_bits).exp2();
+
+    // The float-to-integer cast saturates, and each mass is at most one.
+    pmf.map(|p| (p * scale).round() as usize).collect()
+}
+
+/// Quantize a unimodal pmf over `0, 1, 2, ...` until it has passed `mode` and its masses round to
+/// zero, at which point the remaining tail is below the requested precision.
+fn quantize_tail(ln_pmf: impl Fn(usize) -> f64, mode: usize, precision_bits: u32) -> Vec<usize> {
+    let mut weights = quantize(
+        (0..)
+            .map(|k| (k, ln_pmf(k).exp()))
+            .take_while(|&(k, p)| k <= mode || (p * f64::from(precision_bits).exp2()) >= 0.5)
+            .map(|(_, p)| p),
+        precision_bits,
+    );
+
+    // Drop any trailing zero weights to keep the tree small.
+    while weights.last() == Some(&0) {
+        weights.pop();
+    }
+    weights
+}
+
+/// Create a generator for the binomial distribution: the number of successes in `n` independent
+/// trials that each succeed with probability `p`.
+///
+/// Only the outcomes around the mode whose masses do not round to zero at the requested precision
+/// are evaluated and kept, so the generator stays small even for a huge `n`. The masses are
+/// normalized over the outcomes kept, and the number of successes is the sampled index plus the
+/// returned offset, i.e. the smallest number of successes kept.
+/// # Panics
+/// Will panic if `p` is not strictly between zero and one, if `precision_bits` is too large for a
+/// `usize`, or if fewer than two outcomes have a non-zero weight at the requested precision.
+#[must_use]
+pub fn binomial(n: usize, p: f64, precision_bits: u32) -> (usize, Generator) {
+    assert!(p > 0. && p < 1., "The probability must be in (0, 1).");
+    let odds = p / (1. - p);
+
+    // Walk outwards from the mode with the ratios of consecutive masses, relative to the mass of
+    // the mode, until the masses are too small to round to a non-zero weight. Each mass is at most
+    // its ratio to the mode, so no outcome with a non-zero weight is skipped.
+    let cutoff = 0.5 / f64::from(precision_bits).exp2();
+    let mode = (((n + 1) as f64 * p).floor() as usize).min(n);
+    let mut masses = Vec::new();
+    let mut mass = 1.;
+    for k in (1..=mode).rev() {
+        mass *= k as f64 / ((n - k + 1) as f64 * odds);
+        if mass < cutoff {
+            break;
+        }
+        masses.push(mass);
+    }
+    let offset = mode - masses.len();
+    masses.reverse();
+    masses.push(1.);
+    let mut mass = 1.;
+    for k in mode..n {
+        mass *= (n - k) as f64 * odds / (k + 1) as f64;
+        if mass < cutoff {
+            break;
+        }
+        masses.push(mass);
+    }
+
+    let total: f64 = masses.iter().sum();
+    let mut weights = quantize(masses.iter().map(|m| m / total), precision_bits);
+
+    // Drop the outcomes at either end whose normalized masses round to zero.
+    let leading = weights.iter().take_while(|&&w| w == 0).count();
+    weights.drain(..leading);
+    while weights.last() == Some(&0) {
+        weights.pop();
+    }
+    (offset + leading, Generator::new(&weights))
+}
+
+/// Create a generator for the geometric distribution: the number of failures before the first
+/// success of independent trials that each succeed with probability `p`. The unbounded tail is
+/// truncated once its masses round to zero at the requested precision.
+/// # Panics
+/// Will panic if `p` is not strictly between zero and one, if `precision_bits` is too large for a
+/// `usize`, or if fewer than two outcomes have a non-zero weight at the requested precision.
+#[must_use]
+pub fn geometric(p: f64, precision_bits: u32) -> Generator {
+    assert!(p > 0. && p < 1., "The probability must be in (0, 1).");
+    let (ln_p, ln_q) = (p.ln(), (-p).ln_1p());
+    Generator::new(&quantize_tail(
+        |k| k as f64 * ln_q + ln_p,
+        0,
+        precision_bits,
+    ))
+}
+
+/// Create a generator for the Poisson distribution with mean `lambda`. The unbounded tail is
+/// truncated once its masses round to zero at the requested precision.
+/// # Panics
+/// Will panic if `lambda` is not positive and finite, if `precision_bits` is too large for a
+/// `usize`, or if fewer than two outcomes have a non-zero weight at the requested precision.
+#[must_use]
+pub fn poisson(lambda: f64, precision_bits: u32) -> Generator {
+    assert!(
+        lambda > 0. && lambda.is_finite(),
+        "The mean must be positive and finite."
+    );
+    let ln_lambda = lambda.ln();
+
+    // Memoize `ln(k!)` as `k` increases, since the terms are evaluated in order.
+    let ln_factorial = std::cell::Cell::new((0, 0.));
+    let ln_pmf = |k: usize| {
+        let (mut i, mut ln_fact) = ln_factorial.get();
+        while i < k {
+            i += 1;
+            ln_fact += (i as f64).ln();
+        }
+        ln_factorial.set((i, ln_fact));
+        k as f64 * ln_lambda - lambda - ln_fact
+    };
+
+    // The mode of the Poisson distribution is `floor(lambda)`.
+    let mode = lambda.floor() as usize;
+    Generator::new(&quantize_tail(ln_pmf, mode, precision_bits))
+}
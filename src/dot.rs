@@ -0,0 +1,71 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Rendering a DDG tree in the Graphviz DOT language, for visualizing how FLDR samples.
+
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::Write;
+
+use crate::{label::Label, levels, Generator};
+
+impl<L: Label> Generator<L> {
+    /// Render the DDG tree as a Graphviz `digraph`, e.g. to plot it with `dot -Tsvg`.
+    ///
+    /// Internal nodes are drawn as points and leaves as boxes holding their bucket index. Every
+    /// edge is labeled with the coin flip that takes it, `0` for `false` and `1` for `true`. Leaves
+    /// of the padding label are drawn dashed, with a dashed back-edge to the root, since a sample
+    /// that reaches one restarts from the root.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph ddg {\n    root [shape=point];\n");
+
+        // Each level holds the two children of every internal node above it, leaves first.
+        let mut parents = vec![String::from("root")];
+        for (j, labels) in levels(&self.level_label_matrix).enumerate() {
+            let mut internal = Vec::new();
+            let children = parents.iter().flat_map(|parent| [(parent, 0), (parent, 1)]);
+            for (i, (parent, toss)) in children.enumerate() {
+                let node = format!("n{j}_{i}");
+                match labels.get(i).map(|&label| label.to_usize()) {
+                    Some(label) if label < self.bucket_count => {
+                        writeln!(dot, "    {node} [shape=box, label=\"{label}\"];")
+                    }
+                    Some(_) => writeln!(
+                        dot,
+                        "    {node} [shape=box, style=dashed, label=\"reject\"];\n    \
+                         {node} -> root [style=dashed, constraint=false];"
+                    ),
+                    None => writeln!(dot, "    {node} [shape=point];"),
+                }
+                .expect("Writing to a `String` cannot fail.");
+                writeln!(dot, "    {parent} -> {node} [label=\"{toss}\"];")
+                    .expect("Writing to a `String` cannot fail.");
+                if i >= labels.len() {
+                    internal.push(node);
+                }
+            }
+            parents = internal;
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
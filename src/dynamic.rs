@@ -0,0 +1,110 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Sampling from a distribution whose weights change constantly.
+
+use alloc::vec::Vec;
+
+use crate::{fenwick::Fenwick, uniform::Uniform, FairCoin};
+
+/// A sampler that supports changing any weight in logarithmic time.
+///
+/// The weights are kept in a Fenwick tree of cumulative sums. Each sample draws a uniformly random
+/// position below the total weight with the Fast Dice Roller, then finds the bucket covering that
+/// position in logarithmic time, so samples are exact but use somewhat more entropy and time than
+/// a `Generator`. Prefer a `Generator` for distributions that change rarely.
+#[derive(Clone, Debug)]
+pub struct DynamicSampler {
+    weights: Vec<usize>,
+    tree: Fenwick,
+    total: usize,
+}
+
+impl DynamicSampler {
+    /// Create a sampler over `weights`.
+    /// # Panics
+    /// Will panic if the sum of the weights overflows a `usize`.
+    #[must_use]
+    pub fn new(weights: &[usize]) -> Self {
+        let total = weights
+            .iter()
+            .try_fold(0usize, |sum, &w| sum.checked_add(w))
+            .expect("The sum of the weights must fit in a `usize`.");
+        Self {
+            weights: weights.to_vec(),
+            tree: Fenwick::new(weights),
+            total,
+        }
+    }
+
+    /// The number of buckets, including any with zero weight.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Whether there are no buckets.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// The current weight of every bucket.
+    #[must_use]
+    pub fn weights(&self) -> &[usize] {
+        &self.weights
+    }
+
+    /// The sum of the current weights.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Change the weight of bucket `index` to `weight`.
+    /// # Panics
+    /// Will panic if `index` is out of bounds, or if the new sum of the weights overflows a `usize`.
+    pub fn set_weight(&mut self, index: usize, weight: usize) {
+        let old = self.weights[index];
+        if weight >= old {
+            let delta = weight - old;
+            self.total = self
+                .total
+                .checked_add(delta)
+                .expect("The sum of the weights must fit in a `usize`.");
+            self.tree.add(index, delta);
+        } else {
+            let delta = old - weight;
+            self.total -= delta;
+            self.tree.sub(index, delta);
+        }
+        self.weights[index] = weight;
+    }
+
+    /// Sample a random index in the range `0..len()` using a given `FairCoin`.
+    /// # Panics
+    /// Will panic if every weight is zero.
+    pub fn sample(&self, fair_coin: &mut impl FairCoin) -> usize {
+        assert!(self.total > 0, "At least one weight must be non-zero.");
+        self.tree.search(Uniform::new(self.total).sample(fair_coin))
+    }
+}
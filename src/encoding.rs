@@ -0,0 +1,115 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! A compact, versioned, endian-stable binary encoding of a `Generator`'s DDG tree, for embedding
+//! preprocessed trees in firmware images or loading them with `include_bytes!` without serde.
+//!
+//! An encoded tree is laid out as follows, with every integer little-endian regardless of the
+//! target, and every word stored as a `u64` regardless of pointer width:
+//!
+//! | Offset   | Size      | Contents                                                       |
+//! |----------|-----------|----------------------------------------------------------------|
+//! | 0        | 4         | The magic bytes `FLDT`                                         |
+//! | 4        | 1         | The format version, currently `1`                              |
+//! | 5        | 3         | Reserved, always zero                                          |
+//! | 8        | 8         | The number of buckets                                          |
+//! | 16       | 8         | The number `n` of words in the packed level-label matrix       |
+//! | 24       | `8 * n`   | The words of the matrix                                        |
+//! | `24 + 8n`| 8         | The 64-bit FNV-1a hash of every preceding byte                 |
+//!
+//! Decoding checks the header and the hash, and then checks the tree itself as deserializing does,
+//! so a truncated or corrupted blob is reported as an `Error` rather than becoming a sampler.
+
+use alloc::{sync::Arc, vec::Vec};
+
+use crate::{label::Label, Error, Generator};
+
+/// The magic bytes that open every encoded tree.
+pub const MAGIC: [u8; 4] = *b"FLDT";
+
+/// The version of the format written by `Generator::to_bytes`.
+pub const VERSION: u8 = 1;
+
+/// The size in bytes of the header that precedes the words of the matrix.
+const HEADER_LEN: usize = 24;
+
+impl<L: Label> Generator<L> {
+    /// Encode the DDG tree in the binary format described in the [module documentation](self).
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let words = self.level_label_matrix.len();
+        let mut bytes = Vec::with_capacity(HEADER_LEN + 8 * words + 8);
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&[VERSION, 0, 0, 0]);
+        bytes.extend_from_slice(&(self.bucket_count as u64).to_le_bytes());
+        bytes.extend_from_slice(&(words as u64).to_le_bytes());
+        for word in self.level_label_matrix.iter() {
+            bytes.extend_from_slice(&(word.to_usize() as u64).to_le_bytes());
+        }
+        bytes.extend_from_slice(&fnv1a(&bytes).to_le_bytes());
+        bytes
+    }
+}
+
+impl Generator {
+    /// Decode a DDG tree written by `Generator::to_bytes`, e.g. one embedded with
+    /// `include_bytes!`. Decoding takes time linear in the size of the tree, and never rebuilds it.
+    /// # Errors
+    /// Returns `Error::InvalidEncoding` if the bytes are truncated, have trailing data, do not
+    /// start with the magic bytes of a supported version, fail the hash check, or hold a value
+    /// that does not fit in a `usize` on this target. Returns `Error::MalformedTree` if the tree
+    /// they describe could leave its bounds or never terminate.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let invalid = Err(Error::InvalidEncoding);
+        let Some((body, hash)) = bytes.split_last_chunk::<8>() else {
+            return invalid;
+        };
+        if body.len() < HEADER_LEN
+            || body[..4] != MAGIC
+            || body[4..8] != [VERSION, 0, 0, 0]
+            || fnv1a(body) != u64::from_le_bytes(*hash)
+        {
+            return invalid;
+        }
+
+        let mut words = body[8..].chunks_exact(8).map(|chunk| {
+            let word = u64::from_le_bytes(chunk.try_into().expect("Chunks are eight bytes long."));
+            usize::try_from(word).map_err(|_| Error::InvalidEncoding)
+        });
+        let (Some(bucket_count), Some(len)) = (words.next(), words.next()) else {
+            return invalid;
+        };
+        let (bucket_count, len) = (bucket_count?, len?);
+        if len.checked_mul(8) != Some(body.len() - HEADER_LEN) {
+            return invalid;
+        }
+        let level_label_matrix = words.collect::<Result<Arc<[usize]>, _>>()?;
+        Self::try_from_matrix(bucket_count, level_label_matrix)
+    }
+}
+
+/// The 64-bit FNV-1a hash of `bytes`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
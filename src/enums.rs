@@ -0,0 +1,122 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Sampling enums directly, such as loot tables and the states of a state machine.
+//!
+//! With the `derive` feature, `#[derive(WeightedSample)]` implements `WeightedSample` for an enum
+//! of unit variants from a `#[weight = N]` attribute on each variant:
+//!
+//! ```
+//! # #[cfg(feature = "derive")]
+//! # {
+//! use fast_loaded_dice_roller as fldr;
+//! use fldr::WeightedSample;
+//! # struct AlternatingCoin(bool);
+//! # impl fldr::FairCoin for AlternatingCoin {
+//! #     fn flip(&mut self) -> bool {
+//! #         self.0 = !self.0;
+//! #         self.0
+//! #     }
+//! # }
+//! # let mut coin = AlternatingCoin(false);
+//!
+//! #[derive(Debug, PartialEq, WeightedSample)]
+//! enum Loot {
+//!     #[weight = 60]
+//!     Gold,
+//!     #[weight = 35]
+//!     Potion,
+//!     #[weight = 5]
+//!     Sword,
+//! }
+//!
+//! let loot = Loot::sampler();
+//! let drop: Loot = loot.sample(&mut coin);
+//! # }
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::{FairCoin, Generator};
+
+/// An enum whose variants can be sampled by weight, usually implemented with
+/// `#[derive(WeightedSample)]` under the `derive` feature.
+pub trait WeightedSample: Sized {
+    /// The weight of each variant, in the order the variants are declared.
+    const WEIGHTS: &'static [usize];
+
+    /// The variant at position `index` of `WEIGHTS`.
+    fn from_index(index: usize) -> Self;
+
+    /// Build a sampler over the variants.
+    /// # Panics
+    /// Will panic if `WEIGHTS` is invalid for `Generator::new`.
+    #[must_use]
+    fn sampler() -> EnumSampler<Self> {
+        EnumSampler::new()
+    }
+}
+
+/// A `Generator` over the variants of an enum, whose `sample` returns the variant itself.
+pub struct EnumSampler<E> {
+    generator: Generator,
+    variants: PhantomData<fn() -> E>,
+}
+
+impl<E: WeightedSample> EnumSampler<E> {
+    /// Build a sampler over the variants of `E`.
+    /// # Panics
+    /// Will panic if `E::WEIGHTS` is invalid for `Generator::new`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            generator: Generator::new(E::WEIGHTS),
+            variants: PhantomData,
+        }
+    }
+
+    /// Sample a random variant using a given `FairCoin`.
+    pub fn sample(&self, fair_coin: &mut impl FairCoin) -> E {
+        E::from_index(self.generator.sample(fair_coin))
+    }
+
+    /// The generator over the indices of the variants.
+    #[must_use]
+    pub fn generator(&self) -> &Generator {
+        &self.generator
+    }
+}
+
+impl<E: WeightedSample> Default for EnumSampler<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> Clone for EnumSampler<E> {
+    fn clone(&self) -> Self {
+        Self {
+            generator: self.generator.clone(),
+            variants: PhantomData,
+        }
+    }
+}
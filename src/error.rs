@@ -0,0 +1,90 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use core::fmt;
+
+/// The ways in which building or sampling a DDG tree can fail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The distribution has less than two non-zero weights, so there is nothing to sample.
+    TooFewNonZeroWeights,
+
+    /// The sum of the weights does not fit in a `usize` on this target.
+    WeightSumOverflow,
+
+    /// The level-label matrix would have more entries than a `usize` can address.
+    TreeTooLarge,
+
+    /// A traversal of the DDG tree left the bounds of the tree.
+    MalformedTree,
+
+    /// An encoded DDG tree is truncated, corrupted, or in an unsupported format or version.
+    InvalidEncoding,
+
+    /// The distribution has more buckets than the configured limit, or than its labels can index.
+    TooManyBuckets,
+
+    /// The DDG tree would be deeper than the configured limit.
+    TooDeep,
+
+    /// The level-label matrix would use more memory than the configured limit.
+    TooLarge,
+
+    /// The coin failed a continuous health test, so its entropy source may be stuck or biased.
+    UnhealthyCoin,
+
+    /// The weights were given in fixed units, such as percentages, but do not sum to the whole.
+    WrongTotal {
+        /// The sum the weights must have, e.g. 100 for percentages.
+        expected: usize,
+
+        /// The sum of the weights that were given.
+        actual: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::TooFewNonZeroWeights => {
+                "The distribution must have at least two non-zero weights."
+            }
+            Self::WeightSumOverflow => "The sum of the weights must fit in a `usize`.",
+            Self::TreeTooLarge => "The level-label matrix must be addressable by a `usize`.",
+            Self::MalformedTree => "The DDG tree is malformed.",
+            Self::InvalidEncoding => "The encoded DDG tree is invalid.",
+            Self::TooManyBuckets => "The distribution has more buckets than the limit.",
+            Self::TooDeep => "The DDG tree would be deeper than the limit.",
+            Self::TooLarge => "The level-label matrix would be larger than the limit.",
+            Self::UnhealthyCoin => "The coin failed a health test.",
+            Self::WrongTotal { expected, actual } => {
+                return write!(
+                    f,
+                    "The weights must sum to {expected}, but sum to {actual}."
+                );
+            }
+        })
+    }
+}
+
+impl core::error::Error for Error {}
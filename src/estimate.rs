@@ -0,0 +1,153 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Estimation of the weights of a loaded die from observed sample counts.
+//!
+//! The maximum likelihood estimate of a multinomial distribution is the observed frequency of
+//! each bucket, so the estimated weights are simply the counts. Each probability is paired with a
+//! Wilson score interval, a normal approximation that stays within `[0, 1]` and behaves well for
+//! small counts.
+
+use crate::Generator;
+
+/// The estimated distribution behind a histogram of observed counts.
+#[derive(Clone, Debug)]
+pub struct Estimate {
+    counts: Vec<usize>,
+    probabilities: Vec<f64>,
+    intervals: Vec<(f64, f64)>,
+}
+
+impl Estimate {
+    /// Estimate the distribution that produced `counts`, with confidence intervals at the given
+    /// `confidence` level (e.g. `0.95`).
+    /// # Panics
+    /// Will panic if every count is zero or if `confidence` is not strictly between zero and one.
+    #[must_use]
+    pub fn new(counts: &[usize], confidence: f64) -> Self {
+        assert!(
+            confidence > 0. && confidence < 1.,
+            "The confidence level must be in (0, 1)."
+        );
+        let total = counts.iter().sum::<usize>();
+        assert!(total > 0, "At least one sample must be observed.");
+
+        let n = total as f64;
+        let z = standard_normal_quantile(0.5 + confidence / 2.);
+        let z2 = z * z;
+        let (probabilities, intervals) = counts
+            .iter()
+            .map(|&c| {
+                let p = c as f64 / n;
+                let denominator = 1. + z2 / n;
+                let center = (p + z2 / (2. * n)) / denominator;
+                let half_width = z / denominator * (p * (1. - p) / n + z2 / (4. * n * n)).sqrt();
+                (
+                    p,
+                    ((center - half_width).max(0.), (center + half_width).min(1.)),
+                )
+            })
+            .unzip();
+
+        Self {
+            counts: counts.to_vec(),
+            probabilities,
+            intervals,
+        }
+    }
+
+    /// The maximum likelihood estimate of each bucket's probability.
+    #[must_use]
+    pub fn probabilities(&self) -> &[f64] {
+        &self.probabilities
+    }
+
+    /// The `(lower, upper)` confidence interval of each bucket's probability.
+    #[must_use]
+    pub fn intervals(&self) -> &[(f64, f64)] {
+        &self.intervals
+    }
+
+    /// The estimated integer weights, which are the observed counts.
+    #[must_use]
+    pub fn weights(&self) -> &[usize] {
+        &self.counts
+    }
+
+    /// Create a generator that samples exactly from the maximum likelihood estimate.
+    /// # Panics
+    /// Will panic if less than two buckets were observed.
+    #[must_use]
+    pub fn to_generator(&self) -> Generator {
+        Generator::new(&self.counts)
+    }
+}
+
+/// The quantile function of the standard normal distribution, using Acklam's rational
+/// approximation (relative error below `1.2e-9`).
+fn standard_normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.)
+    };
+    if p < P_LOW {
+        tail((-2. * p.ln()).sqrt())
+    } else if p <= 1. - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.)
+    } else {
+        -tail((-2. * (1. - p).ln()).sqrt())
+    }
+}
@@ -0,0 +1,106 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! A Fenwick tree (binary indexed tree) of weights, supporting logarithmic-time updates and
+//! search by cumulative weight.
+
+use alloc::{vec, vec::Vec};
+
+/// Prefix sums over a list of weights, stored implicitly in a binary indexed tree.
+#[derive(Clone, Debug)]
+pub(crate) struct Fenwick {
+    tree: Vec<usize>,
+}
+
+impl Fenwick {
+    /// Build a tree over `weights` in linear time.
+    pub(crate) fn new(weights: &[usize]) -> Self {
+        // `tree[i]` holds the sum of the `i & -i` weights ending at the 1-based index `i`.
+        let mut tree = vec![0; weights.len() + 1];
+        for (i, &w) in weights.iter().enumerate() {
+            let i = i + 1;
+            tree[i] += w;
+            let parent = i + (i & i.wrapping_neg());
+            if parent < tree.len() {
+                tree[parent] += tree[i];
+            }
+        }
+        Self { tree }
+    }
+
+    /// The number of weights.
+    pub(crate) fn len(&self) -> usize {
+        self.tree.len() - 1
+    }
+
+    /// Add `delta` to the weight at `index`. The new total must fit in a `usize`.
+    pub(crate) fn add(&mut self, index: usize, delta: usize) {
+        let mut i = index + 1;
+        while i < self.tree.len() {
+            self.tree[i] += delta;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// Subtract `delta` from the weight at `index`, which must be at least `delta`.
+    pub(crate) fn sub(&mut self, index: usize, delta: usize) {
+        let mut i = index + 1;
+        while i < self.tree.len() {
+            self.tree[i] -= delta;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// The sum of the weights at indices `0..end`.
+    pub(crate) fn prefix_sum(&self, end: usize) -> usize {
+        let mut sum = 0;
+        let mut i = end;
+        while i > 0 {
+            sum += self.tree[i];
+            i -= i & i.wrapping_neg();
+        }
+        sum
+    }
+
+    /// The sum of all weights.
+    pub(crate) fn total(&self) -> usize {
+        self.prefix_sum(self.len())
+    }
+
+    /// The index whose weight covers the cumulative position `target`, i.e. the smallest index `i`
+    /// such that `prefix_sum(i + 1) > target`. `target` must be less than `total()`.
+    pub(crate) fn search(&self, mut target: usize) -> usize {
+        let mut position = 0;
+        let mut step = (self.tree.len() - 1)
+            .checked_next_power_of_two()
+            .unwrap_or(0);
+        while step > 0 {
+            let next = position + step;
+            if next < self.tree.len() && self.tree[next] <= target {
+                position = next;
+                target -= self.tree[next];
+            }
+            step >>= 1;
+        }
+        position
+    }
+}
@@ -0,0 +1,129 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Expanding weighted context-free grammars, for procedural names, text, and quests.
+
+use alloc::vec::Vec;
+
+use crate::{choice::Choice, FairCoin};
+
+/// One symbol on the right-hand side of a grammar rule.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Symbol<T> {
+    /// A value that is copied into the produced sequence as is.
+    Terminal(T),
+
+    /// The index of the rule to expand in place of this symbol.
+    Nonterminal(usize),
+}
+
+/// The weighted alternatives of one nonterminal.
+#[derive(Clone)]
+struct Rule<T> {
+    alternatives: Vec<Vec<Symbol<T>>>,
+    choice: Choice,
+}
+
+/// A context-free grammar in which each nonterminal chooses between its alternatives with
+/// probability proportional to their weights.
+#[derive(Clone)]
+pub struct Grammar<T> {
+    rules: Vec<Rule<T>>,
+}
+
+impl<T: Clone> Grammar<T> {
+    /// Create a grammar from its rules, where rule `i` lists the `(weight, symbols)` alternatives
+    /// that `Symbol::Nonterminal(i)` expands to.
+    /// # Panics
+    /// Will panic if a rule has no alternative with a non-zero weight, or if a symbol refers to a
+    /// rule that does not exist.
+    #[must_use]
+    pub fn new(rules: Vec<Vec<(usize, Vec<Symbol<T>>)>>) -> Self {
+        let rule_count = rules.len();
+        let rules = rules
+            .into_iter()
+            .map(|alternatives| {
+                let weights: Vec<_> = alternatives.iter().map(|(weight, _)| *weight).collect();
+                let choice = Choice::new(&weights)
+                    .expect("Every rule must have an alternative with a non-zero weight.");
+                let alternatives: Vec<_> = alternatives
+                    .into_iter()
+                    .map(|(_, symbols)| symbols)
+                    .collect();
+                assert!(
+                    alternatives.iter().flatten().all(|symbol| match symbol {
+                        Symbol::Terminal(_) => true,
+                        Symbol::Nonterminal(i) => *i < rule_count,
+                    }),
+                    "Every nonterminal must refer to a rule of the grammar."
+                );
+                Rule {
+                    alternatives,
+                    choice,
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// The number of rules, i.e. nonterminals, in the grammar.
+    #[must_use]
+    pub fn rule_count(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Expand the nonterminal `start` into a sequence of terminals, using a given `FairCoin`, or
+    /// return `None` if the expansion nests more than `max_depth` rules deep.
+    pub fn expand(
+        &self,
+        fair_coin: &mut impl FairCoin,
+        start: usize,
+        max_depth: usize,
+    ) -> Option<Vec<T>> {
+        let mut output = Vec::new();
+        self.expand_into(fair_coin, start, max_depth, &mut output)
+            .then_some(output)
+    }
+
+    /// Append the expansion of rule `i` to `output`, returning whether it stayed within the depth.
+    fn expand_into(
+        &self,
+        fair_coin: &mut impl FairCoin,
+        i: usize,
+        depth: usize,
+        output: &mut Vec<T>,
+    ) -> bool {
+        let Some(depth) = depth.checked_sub(1) else {
+            return false;
+        };
+        let rule = &self.rules[i];
+        rule.alternatives[rule.choice.sample(fair_coin)]
+            .iter()
+            .all(|symbol| match symbol {
+                Symbol::Terminal(value) => {
+                    output.push(value.clone());
+                    true
+                }
+                Symbol::Nonterminal(j) => self.expand_into(fair_coin, *j, depth, output),
+            })
+    }
+}
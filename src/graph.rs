@@ -0,0 +1,127 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Random walks on graphs with weighted edges, for simulating processes on networks.
+
+use alloc::{vec, vec::Vec};
+use core::cell::OnceCell;
+
+use crate::{choice::Choice, FairCoin};
+
+/// A directed graph whose edges have integer weights, with a DDG tree built for each node the
+/// first time a walk leaves it.
+#[derive(Clone)]
+pub struct WeightedGraph {
+    adjacency: Vec<Vec<(usize, usize)>>,
+    choices: Vec<OnceCell<Option<Choice>>>,
+}
+
+impl WeightedGraph {
+    /// Create a graph from adjacency lists, where list `i` holds the `(target, weight)` pairs of
+    /// the edges leaving node `i`. Nodes whose edges all have zero weight are dead ends.
+    /// # Panics
+    /// Will panic if an edge targets a node that does not exist.
+    #[must_use]
+    pub fn new(adjacency: Vec<Vec<(usize, usize)>>) -> Self {
+        let node_count = adjacency.len();
+        assert!(
+            adjacency
+                .iter()
+                .flatten()
+                .all(|&(target, _)| target < node_count),
+            "Every edge must target a node of the graph."
+        );
+        Self {
+            choices: vec![OnceCell::new(); node_count],
+            adjacency,
+        }
+    }
+
+    /// The number of nodes in the graph.
+    #[must_use]
+    pub fn node_count(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    /// The `(target, weight)` pairs of the edges leaving `node`.
+    #[must_use]
+    pub fn edges(&self, node: usize) -> &[(usize, usize)] {
+        &self.adjacency[node]
+    }
+
+    /// Follow one edge out of `node`, chosen with probability proportional to its weight, or
+    /// return `None` if `node` is a dead end.
+    pub fn step(&self, fair_coin: &mut impl FairCoin, node: usize) -> Option<usize> {
+        let edges = &self.adjacency[node];
+        let choice = self.choices[node].get_or_init(|| {
+            let weights: Vec<_> = edges.iter().map(|&(_, weight)| weight).collect();
+            Choice::new(&weights)
+        });
+        choice
+            .as_ref()
+            .map(|choice| edges[choice.sample(fair_coin)].0)
+    }
+
+    /// Walk the graph for up to `len` nodes, including `start`, stopping early at a dead end.
+    pub fn walk(&self, fair_coin: &mut impl FairCoin, start: usize, len: usize) -> Vec<usize> {
+        let mut walk = Vec::with_capacity(len);
+        let mut node = Some(start);
+        while let Some(current) = node.filter(|_| walk.len() < len) {
+            walk.push(current);
+            node = self.step(fair_coin, current);
+        }
+        walk
+    }
+
+    /// Walk the graph for exactly `len` nodes, including `start`, returning to `start` with
+    /// probability `numerator / denominator` before each step and whenever a dead end is reached,
+    /// as in personalized PageRank.
+    /// # Panics
+    /// Will panic if `numerator / denominator` is not in `[0, 1]`.
+    pub fn walk_with_restart(
+        &self,
+        fair_coin: &mut impl FairCoin,
+        start: usize,
+        len: usize,
+        numerator: usize,
+        denominator: usize,
+    ) -> Vec<usize> {
+        assert!(
+            numerator <= denominator && denominator > 0,
+            "The restart probability must be in [0, 1]."
+        );
+        let restart = Choice::new(&[denominator - numerator, numerator])
+            .expect("the denominator is non-zero");
+
+        let mut walk = Vec::with_capacity(len);
+        let mut node = start;
+        while walk.len() < len {
+            walk.push(node);
+            node = if restart.sample(fair_coin) == 1 {
+                start
+            } else {
+                self.step(fair_coin, node).unwrap_or(start)
+            };
+        }
+        walk
+    }
+}
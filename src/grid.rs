@@ -0,0 +1,168 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Sampling coordinates from two-dimensional weight maps, such as spawn maps and heatmaps.
+
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use crate::{choice::Choice, probability::gcd, FairCoin};
+
+/// Samples `(row, col)` coordinates with probability proportional to a matrix of weights.
+///
+/// The matrix is flattened in row-major order into a single DDG tree, so each sample costs the
+/// same as sampling a `Generator` over every cell, and the flat index is mapped back to its
+/// coordinates.
+#[derive(Clone)]
+pub struct Grid2D {
+    rows: usize,
+    cols: usize,
+    choice: Choice,
+}
+
+impl Grid2D {
+    /// Create a sampler over a `rows` by `cols` matrix, given its weights in row-major order.
+    /// # Panics
+    /// Will panic if there are not `rows * cols` weights, if every weight is zero, or if the sum of
+    /// the weights overflows a `usize`.
+    #[must_use]
+    pub fn new(rows: usize, cols: usize, weights: &[usize]) -> Self {
+        assert!(
+            rows.checked_mul(cols) == Some(weights.len()),
+            "There must be one weight for each cell of the grid."
+        );
+        Self {
+            rows,
+            cols,
+            choice: Choice::new(weights).expect("At least one cell must have a non-zero weight."),
+        }
+    }
+
+    /// The number of rows in the grid.
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The number of columns in the grid.
+    #[must_use]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Sample the `(row, col)` coordinates of a cell, using a given `FairCoin`.
+    pub fn sample(&self, fair_coin: &mut impl FairCoin) -> (usize, usize) {
+        let i = self.choice.sample(fair_coin);
+        (i / self.cols, i % self.cols)
+    }
+}
+
+/// Samples `(row, col)` coordinates like `Grid2D`, but by first choosing a row from the row sums and
+/// then a column from that row's own DDG tree.
+///
+/// Rows whose weights are proportional to each other, such as repeated rows, share one tree, so a
+/// matrix with few distinct row shapes is stored in much less memory than its flattened form.
+#[derive(Clone)]
+pub struct Generator2D {
+    rows: usize,
+    cols: usize,
+    marginal: Choice,
+    conditionals: Vec<Choice>,
+    row_conditionals: Vec<usize>,
+}
+
+impl Generator2D {
+    /// Create a sampler over a `rows` by `cols` matrix, given its weights in row-major order.
+    /// # Panics
+    /// Will panic if there are not `rows * cols` weights, if every weight is zero, or if the sum of
+    /// the weights overflows a `usize`.
+    #[must_use]
+    pub fn new(rows: usize, cols: usize, weights: &[usize]) -> Self {
+        assert!(
+            rows.checked_mul(cols) == Some(weights.len()),
+            "There must be one weight for each cell of the grid."
+        );
+        let row_sums: Vec<usize> = weights
+            .chunks(cols.max(1))
+            .map(|row| row.iter().try_fold(0usize, |sum, &w| sum.checked_add(w)))
+            .collect::<Option<_>>()
+            .expect("The sum of the weights must fit in a `usize`.");
+        row_sums
+            .iter()
+            .try_fold(0usize, |sum, &w| sum.checked_add(w))
+            .expect("The sum of the weights must fit in a `usize`.");
+        let marginal =
+            Choice::new(&row_sums).expect("At least one cell must have a non-zero weight.");
+
+        // Rows are reduced by the GCD of their weights, so that proportional rows share a tree.
+        let mut shapes = BTreeMap::new();
+        let mut conditionals = Vec::new();
+        let row_conditionals = weights
+            .chunks(cols.max(1))
+            .zip(&row_sums)
+            .map(|(row, &sum)| {
+                if sum == 0 {
+                    // A row without weight is never chosen, so any tree may stand in for it.
+                    return 0;
+                }
+                let divisor = row.iter().fold(0, |d, &w| gcd(d, w));
+                let shape: Vec<usize> = row.iter().map(|&w| w / divisor).collect();
+                *shapes.entry(shape).or_insert_with_key(|shape| {
+                    conditionals.push(Choice::new(shape).expect("The row has a non-zero weight."));
+                    conditionals.len() - 1
+                })
+            })
+            .collect();
+
+        Self {
+            rows,
+            cols,
+            marginal,
+            conditionals,
+            row_conditionals,
+        }
+    }
+
+    /// The number of rows in the matrix.
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The number of columns in the matrix.
+    #[must_use]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The number of distinct row trees, after proportional rows have been merged.
+    #[must_use]
+    pub fn distinct_rows(&self) -> usize {
+        self.conditionals.len()
+    }
+
+    /// Sample the `(row, col)` coordinates of a cell, using a given `FairCoin`.
+    pub fn sample(&self, fair_coin: &mut impl FairCoin) -> (usize, usize) {
+        let row = self.marginal.sample(fair_coin);
+        let col = self.conditionals[self.row_conditionals[row]].sample(fair_coin);
+        (row, col)
+    }
+}
@@ -0,0 +1,137 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! A reusable harness for catching regressions in the number of coin flips consumed per sample.
+//!
+//! The FLDR paper proves that the expected number of flips per sample is less than `H + 6`,
+//! where `H` is the Shannon entropy of the distribution in bits. Optimizations that change how the
+//! DDG tree is built or traversed can silently waste entropy without affecting correctness, so
+//! this harness measures the average flips per sample over a matrix of distributions and checks it
+//! against the bound. Downstream crates with their own samplers or coins can reuse it through the
+//! `harness` feature.
+
+pub use crate::coin::CountingCoin;
+use crate::{FairCoin, Generator};
+
+/// The additive overhead, in bits, of the FLDR bound on expected flips per sample.
+pub const BOUND_OVERHEAD: f64 = 6.;
+
+/// The default slack, in bits, allowed above the bound for the sampling error of the measurement.
+pub const DEFAULT_TOLERANCE: f64 = 0.5;
+
+/// The Shannon entropy, in bits, of the distribution with the given weights.
+#[must_use]
+pub fn entropy(distribution: &[usize]) -> f64 {
+    let sum = distribution.iter().map(|&w| w as f64).sum::<f64>();
+    distribution
+        .iter()
+        .filter(|&&w| w > 0)
+        .map(|&w| {
+            let p = w as f64 / sum;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// The measured entropy consumption of a distribution.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntropyReport {
+    /// The weights that were sampled.
+    pub distribution: Vec<usize>,
+
+    /// The Shannon entropy of the distribution, in bits.
+    pub entropy: f64,
+
+    /// The average number of flips consumed per sample.
+    pub mean_flips: f64,
+}
+
+impl EntropyReport {
+    /// The FLDR bound on the expected number of flips per sample.
+    #[must_use]
+    pub fn bound(&self) -> f64 {
+        self.entropy + BOUND_OVERHEAD
+    }
+
+    /// Whether the measured average is within `tolerance` bits of the bound.
+    #[must_use]
+    pub fn is_within_bound(&self, tolerance: f64) -> bool {
+        self.mean_flips <= self.bound() + tolerance
+    }
+}
+
+/// Measure the average number of flips per sample over `samples` samples from `distribution`.
+/// # Panics
+/// Will panic if `distribution` is invalid for `Generator::new`, or if `samples` is zero.
+pub fn measure(
+    distribution: &[usize],
+    fair_coin: &mut impl FairCoin,
+    samples: usize,
+) -> EntropyReport {
+    assert!(samples > 0, "At least one sample must be measured.");
+    let generator = Generator::new(distribution);
+    let mut coin = CountingCoin::new(fair_coin);
+    for _ in 0..samples {
+        generator.sample(&mut coin);
+    }
+    EntropyReport {
+        distribution: distribution.to_vec(),
+        entropy: entropy(distribution),
+        mean_flips: coin.flips() as f64 / samples as f64,
+    }
+}
+
+/// A matrix of distributions that exercises the shapes where the bound is tightest: uniform
+/// distributions just above a power of two, dyadic and heavily skewed weights, long tails, and
+/// sums that need most of the bits of a `u32`.
+#[must_use]
+pub fn distribution_matrix() -> Vec<Vec<usize>> {
+    let mut matrix = vec![
+        vec![1, 1],
+        vec![1, 2],
+        vec![1, 1, 2, 4],
+        vec![1, 1 << 20],
+        vec![1, 1, (1 << 31) - 2],
+        vec![3, 5, 7, 11, 13, 17, 19, 23],
+        vec![60, 25, 10, 5],
+    ];
+    matrix.extend([3, 5, 7, 9, 17, 100, 1_000, 1_025].map(|n| vec![1; n]));
+    // A long tail, with weights proportional to `1 / k`.
+    matrix.push((1..=500).map(|k| 720_720 / k).collect());
+    // A geometric tail, with weights halving down to one.
+    matrix.push((0..20).rev().map(|k| 1 << k).collect());
+    matrix
+}
+
+/// Measure every distribution of `distribution_matrix`, returning the reports that exceed the
+/// bound by more than `tolerance` bits.
+pub fn regressions(
+    fair_coin: &mut impl FairCoin,
+    samples: usize,
+    tolerance: f64,
+) -> Vec<EntropyReport> {
+    distribution_matrix()
+        .iter()
+        .map(|distribution| measure(distribution, fair_coin, samples))
+        .filter(|report| !report.is_within_bound(tolerance))
+        .collect()
+}
@@ -0,0 +1,137 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Counts of sampled indices, as produced by batch sampling.
+
+use alloc::{string::ToString, vec, vec::Vec};
+use core::fmt;
+
+/// The number of times each bucket of a distribution was sampled.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Histogram {
+    counts: Vec<usize>,
+}
+
+impl Histogram {
+    /// Create an empty histogram over `bucket_count` buckets.
+    #[must_use]
+    pub fn new(bucket_count: usize) -> Self {
+        Self {
+            counts: vec![0; bucket_count],
+        }
+    }
+
+    /// Record one occurrence of `index`.
+    /// # Panics
+    /// Will panic if `index` is out of bounds.
+    pub fn record(&mut self, index: usize) {
+        self.counts[index] += 1;
+    }
+
+    /// The count of each bucket.
+    #[must_use]
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    /// The total number of recorded samples.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// The most frequently sampled index, preferring the smallest index on ties, or `None` if
+    /// nothing has been recorded.
+    #[must_use]
+    pub fn mode(&self) -> Option<usize> {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &c)| c > 0)
+            .max_by(|(i, a), (j, b)| a.cmp(b).then(j.cmp(i)))
+            .map(|(i, _)| i)
+    }
+
+    /// The mean of the recorded indices, or `None` if nothing has been recorded.
+    #[must_use]
+    pub fn mean_index(&self) -> Option<f64> {
+        let total = self.total();
+        (total > 0).then(|| {
+            self.counts
+                .iter()
+                .enumerate()
+                .map(|(i, &c)| i as f64 * c as f64)
+                .sum::<f64>()
+                / total as f64
+        })
+    }
+
+    /// The fraction of samples that fell in each bucket. Every fraction is zero if nothing has
+    /// been recorded.
+    #[must_use]
+    pub fn normalize(&self) -> Vec<f64> {
+        let total = self.total().max(1) as f64;
+        self.counts.iter().map(|&c| c as f64 / total).collect()
+    }
+
+    /// Add the counts of `other` to this histogram, growing it if `other` has more buckets.
+    pub fn merge(&mut self, other: &Self) {
+        if self.counts.len() < other.counts.len() {
+            self.counts.resize(other.counts.len(), 0);
+        }
+        for (c, &o) in self.counts.iter_mut().zip(&other.counts) {
+            *c += o;
+        }
+    }
+}
+
+impl From<Vec<usize>> for Histogram {
+    fn from(counts: Vec<usize>) -> Self {
+        Self { counts }
+    }
+}
+
+impl AsRef<[usize]> for Histogram {
+    fn as_ref(&self) -> &[usize] {
+        &self.counts
+    }
+}
+
+/// Render the histogram as one line per bucket with its count, percentage, and a bar.
+impl fmt::Display for Histogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const BAR_WIDTH: usize = 40;
+
+        let max = self.counts.iter().copied().max().unwrap_or(0).max(1);
+        let index_width = self.counts.len().saturating_sub(1).to_string().len();
+        let count_width = max.to_string().len();
+        for (i, (&c, p)) in self.counts.iter().zip(self.normalize()).enumerate() {
+            writeln!(
+                f,
+                "{i:>index_width$}: {c:>count_width$} ({:>5.1}%) {}",
+                100. * p,
+                "#".repeat(c * BAR_WIDTH / max)
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The integer types a DDG tree can store its labels and leaf counts in.
+
+pub(crate) mod sealed {
+    /// The conversions used to read and write the levels of a DDG tree. Every value written is at
+    /// most the number of buckets plus one, which the builder checks fits in the label type.
+    pub trait Sealed: Copy + Default {
+        /// Narrow a label or leaf count that is known to fit.
+        fn from_usize(n: usize) -> Self;
+
+        /// Widen a label or leaf count losslessly.
+        fn to_usize(self) -> usize;
+
+        /// Clear the labels of a tree that is being dropped.
+        #[cfg(feature = "zeroize")]
+        fn zeroize(labels: &mut [Self]);
+    }
+}
+
+/// An unsigned integer type that the labels of a DDG tree can be stored in. Narrower labels make
+/// the tree smaller, and so more of it stays in cache while sampling, at the cost of limiting the
+/// number of buckets the distribution may have. See `GeneratorBuilder::build_compact`.
+///
+/// This trait is sealed and implemented for `u16`, `u32`, and `usize`.
+pub trait Label: sealed::Sealed {
+    /// The largest number of buckets, including the padding bucket, whose labels fit in the type.
+    const MAX_BUCKETS: usize;
+}
+
+macro_rules! impl_label {
+    ($($t:ty),*) => {$(
+        impl sealed::Sealed for $t {
+            fn from_usize(n: usize) -> Self {
+                n as $t
+            }
+
+            fn to_usize(self) -> usize {
+                self as usize
+            }
+
+            #[cfg(feature = "zeroize")]
+            fn zeroize(labels: &mut [Self]) {
+                zeroize::Zeroize::zeroize(labels);
+            }
+        }
+
+        impl Label for $t {
+            const MAX_BUCKETS: usize = if <$t>::BITS < usize::BITS {
+                <$t>::MAX as usize
+            } else {
+                usize::MAX
+            };
+        }
+    )*};
+}
+
+impl_label!(u16, u32, usize);
@@ -0,0 +1,116 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Sampling items directly, rather than indices into a separate list of items.
+
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::{Error, FairCoin, Generator};
+
+/// A `Generator` over a list of items, whose `sample` returns the sampled item itself.
+///
+/// ```
+/// use fast_loaded_dice_roller as fldr;
+/// # struct AlternatingCoin(bool);
+/// # impl fldr::FairCoin for AlternatingCoin {
+/// #     fn flip(&mut self) -> bool {
+/// #         self.0 = !self.0;
+/// #         self.0
+/// #     }
+/// # }
+/// # let mut coin = AlternatingCoin(false);
+///
+/// let loot = fldr::labeled::LabeledGenerator::new([("sword", 1), ("potion", 6), ("gold", 3)]);
+/// let drop: &str = loot.sample(&mut coin);
+/// assert!(["sword", "potion", "gold"].contains(&drop));
+/// ```
+#[derive(Clone)]
+pub struct LabeledGenerator<T> {
+    items: Vec<T>,
+    generator: Generator,
+}
+
+impl<T> LabeledGenerator<T> {
+    /// Create a generator over the items of `(item, weight)` pairs, sampling each item with
+    /// probability proportional to its weight.
+    /// # Panics
+    /// Will panic if the weights are invalid for `Generator::new`.
+    #[must_use]
+    pub fn new(pairs: impl IntoIterator<Item = (T, usize)>) -> Self {
+        Self::try_new(pairs).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Create a generator like `LabeledGenerator::new`, but surface invalid weights as an `Error`
+    /// instead of panicking.
+    /// # Errors
+    /// Returns the same errors as `Generator::try_new`.
+    pub fn try_new(pairs: impl IntoIterator<Item = (T, usize)>) -> Result<Self, Error> {
+        let (items, weights): (Vec<_>, Vec<_>) = pairs.into_iter().unzip();
+        Ok(Self {
+            items,
+            generator: Generator::try_new(&weights)?,
+        })
+    }
+
+    /// Sample a random item using a given `FairCoin`.
+    pub fn sample(&self, fair_coin: &mut impl FairCoin) -> &T {
+        &self.items[self.generator.sample(fair_coin)]
+    }
+
+    /// Sample the index of a random item using a given `FairCoin`.
+    pub fn sample_index(&self, fair_coin: &mut impl FairCoin) -> usize {
+        self.generator.sample(fair_coin)
+    }
+
+    /// The items, in the order they were given.
+    #[must_use]
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// The generator over the indices of the items.
+    #[must_use]
+    pub fn generator(&self) -> &Generator {
+        &self.generator
+    }
+
+    /// Consume the generator and return its items.
+    #[must_use]
+    pub fn into_items(self) -> Vec<T> {
+        self.items
+    }
+}
+
+impl Generator {
+    /// Create a generator over the keys of `map`, sampling each key with probability proportional
+    /// to its weight, e.g. tokens weighted by their counts. The keys are cloned into the generator
+    /// in the map's iteration order.
+    /// # Panics
+    /// Will panic if the weights are invalid for `Generator::new`.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn from_map<K: Clone, S>(map: &HashMap<K, usize, S>) -> LabeledGenerator<K> {
+        LabeledGenerator::new(map.iter().map(|(key, &weight)| (key.clone(), weight)))
+    }
+}
@@ -27,6 +27,29 @@
 //! generic over the type of random number generator (RNG) used. There is an optional
 //! implementation, `rand::RngCoin<R>`, that uses the `rand` crate as a dependency for convenience.
 //!
+//! ### Target support
+//! The crate makes no assumptions about pointer width. Weights, their sum, and sampled indices are
+//! all `usize`, so the range of weights accepted by `Generator::new` depends on the target:
+//!
+//! | Pointer width | Example targets               | Maximum sum of weights |
+//! |---------------|-------------------------------|------------------------|
+//! | 16 bits       | AVR, MSP430                   | `65_535`               |
+//! | 32 bits       | wasm32, ARM Cortex-M, i686    | `4_294_967_295`        |
+//! | 64 bits       | x86_64, aarch64               | `2^64 - 1`             |
+//!
+//! Construction panics, rather than silently wrapping, if the sum of the weights does not fit, or
+//! returns `Error::WeightSumOverflow` from `Generator::try_new`. Weights that are wider than a
+//! `usize`, such as `u64` counts on a 32-bit target, can be passed to `Generator::from_weights`,
+//! whose sum may be as wide as the weight type.
+//! `rand::RngCoin<R>` always buffers a `u64` of random bits, regardless of pointer width, so a
+//! given RNG produces the same stream of coin flips on every target.
+//!
+//! ### `no_std` support
+//! The crate is `no_std` compatible when its default `std` feature is disabled, in which case it
+//! only requires `alloc`. The modules that need floating-point functions, hash maps, or thread-local
+//! state (`annealing`, `approximate`, `distance`, `distributions`, `estimate`, and `partitioned`)
+//! are then unavailable, along with `sample_weighted`.
+//!
 //! ### Citation
 //! I neither created nor discovered the FLDR algorithm. This crate is simply an implementation.
 //!
@@ -46,6 +69,24 @@
 //! }
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
+use core::{fmt, ops::RangeBounds};
+
+pub use enums::WeightedSample;
+mod error;
+pub use error::Error;
+#[cfg(feature = "derive")]
+pub use fast_loaded_dice_roller_derive::WeightedSample;
+use histogram::Histogram;
+use label::Label;
+#[cfg(all(feature = "rand", feature = "std"))]
+pub use rand::sample_weighted;
+use weight::Weight;
+
 /// Sampling from the FLDR requires a fair coin, i.e. a random variable that outputs `true` or
 /// `false` with equal probability. This trait describes the interface for a fair coin, but lets
 /// the user choose the specifics of how to implement it.
@@ -56,27 +97,630 @@ pub trait FairCoin {
     fn flip(&mut self) -> bool;
 }
 
+/// Flip a borrowed coin, so that a coin can be lent through generic layers, including as a
+/// `&mut dyn FairCoin`.
+impl<C: FairCoin + ?Sized> FairCoin for &mut C {
+    fn flip(&mut self) -> bool {
+        (**self).flip()
+    }
+}
+
+/// Flip a boxed coin, so that a coin can be stored type-erased as a `Box<dyn FairCoin>`.
+impl<C: FairCoin + ?Sized> FairCoin for Box<C> {
+    fn flip(&mut self) -> bool {
+        (**self).flip()
+    }
+}
+
 /// Represents the discrete-distribution-generator (DDG) tree used to randomly sample items with
 /// specified weights. The FLDR algorithm operates on this object to maintain a size that scales
 /// linearly with the number of bits needed to encode the input distribution.
-pub struct Generator {
+///
+/// The tree is immutable once built and is stored behind an `Arc`, so cloning a `Generator` (e.g.,
+/// to hand a copy to each worker thread) is O(1) and shares the underlying memory.
+///
+/// Each level of the tree is stored as its leaf count followed by exactly that many labels, with
+/// the levels packed back-to-back in a single slice. The tree therefore occupies `depth + leaves`
+/// words, where `leaves` is the number of set bits across the binary expansions of the weights
+/// (and the padding weight that rounds their sum up to a power of two), rather than the
+/// `(n + 2) * depth` words of a dense level-label matrix over `n` buckets. For example, 10,000
+/// weights of one need 10,020 words instead of 140,028. Sampling still walks the slice strictly
+/// forward, so the levels it visits share cache lines.
+///
+/// The words are `usize` by default. Distributions with few enough buckets can store them as `u32`
+/// or `u16` instead with `GeneratorBuilder::build_compact`, which halves or quarters the tree on
+/// 64-bit targets. Only the default `Generator<usize>` can be built directly by the constructors.
+#[derive(Clone)]
+pub struct Generator<L: Label = usize> {
     bucket_count: usize,
-    adjusted_bucket_count: usize,
-    level_label_matrix: Vec<usize>,
+    level_label_matrix: Arc<[L]>,
+}
+
+/// With the `zeroize` feature, the last clone of a `Generator` to be dropped clears the DDG tree,
+/// which encodes the weights of the distribution exactly.
+#[cfg(feature = "zeroize")]
+impl<L: Label> Drop for Generator<L> {
+    fn drop(&mut self) {
+        if let Some(level_label_matrix) = Arc::get_mut(&mut self.level_label_matrix) {
+            L::zeroize(level_label_matrix);
+        }
+    }
+}
+
+/// Shows the DDG tree one level at a time, from the root down, as the labels of that level's
+/// leaves, with the padding label written as `pad`. The number of labels in a level is its leaf
+/// count. The alternate form `{:#?}` puts each level on its own line.
+impl<L: Label> fmt::Debug for Generator<L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /// The leaves of one level, formatted without decoding the padding label as a bucket.
+        struct Level<'a, L: Label>(&'a [L], usize);
+
+        impl<L: Label> fmt::Debug for Level<'_, L> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let mut list = f.debug_list();
+                for label in self.0.iter().map(|&label| label.to_usize()) {
+                    if label < self.1 {
+                        list.entry(&label);
+                    } else {
+                        list.entry(&format_args!("pad"));
+                    }
+                }
+                list.finish()
+            }
+        }
+
+        let levels: Vec<_> = levels(&self.level_label_matrix)
+            .map(|labels| Level(labels, self.bucket_count))
+            .collect();
+        f.debug_struct("Generator")
+            .field("bucket_count", &self.bucket_count)
+            .field("levels", &levels)
+            .finish()
+    }
 }
 
 impl Generator {
     /// Create a new DDG tree for the FLDR algorithm from a list of non-negative integer weights.
     /// # Panics
-    /// Will panic if `distribution` has less than two non-zero weights.
+    /// Will panic if `distribution` has less than two non-zero weights, or if the sum of the
+    /// weights overflows a `usize` (see [Target support](crate#target-support)).
     #[must_use]
     pub fn new(distribution: &[usize]) -> Self {
-        assert!(
-            distribution.iter().filter(|&&w| w > 0).count() >= 2,
-            "The distribution must have at least two non-zero weights."
+        Self::from_layout(Layout::new(distribution), distribution)
+    }
+
+    /// Create a new DDG tree from cumulative weights, where `cumulative[i]` is the total weight of
+    /// the buckets `0..=i`. The per-bucket weights are recovered by differencing.
+    /// # Panics
+    /// Will panic if `cumulative` is not nondecreasing, or if the recovered weights are invalid for
+    /// `Generator::new`.
+    #[must_use]
+    pub fn from_cumulative(cumulative: &[usize]) -> Self {
+        let distribution: Vec<_> = cumulative
+            .iter()
+            .scan(0, |previous, &c| {
+                let w = c.checked_sub(*previous);
+                *previous = c;
+                Some(w.expect("The cumulative weights must be nondecreasing."))
+            })
+            .collect();
+        Self::new(&distribution)
+    }
+
+    /// Create a new DDG tree like `Generator::new`, but surface invalid distributions as an
+    /// `Error` instead of panicking, e.g. when the weights are supplied by users.
+    /// # Errors
+    /// Returns `Error::TooFewNonZeroWeights` if `distribution` has less than two non-zero weights,
+    /// or `Error::WeightSumOverflow` if the sum of the weights overflows a `usize`.
+    pub fn try_new(distribution: &[usize]) -> Result<Self, Error> {
+        Ok(Self::from_layout(
+            Layout::try_new(distribution)?,
+            distribution,
+        ))
+    }
+
+    /// Create a new DDG tree from weights of any unsigned integer type, such as `u64` counts on a
+    /// 32-bit target. The sum of the weights must fit in the wider of `W` and `usize`.
+    /// # Panics
+    /// Will panic if `weights` has less than two non-zero weights, or if their sum overflows.
+    #[must_use]
+    pub fn from_weights<W: Weight>(weights: &[W]) -> Self {
+        Self::from_layout(Layout::new(weights), weights)
+    }
+
+    /// Create a new DDG tree like `Generator::from_weights`, but surface invalid distributions as
+    /// an `Error` instead of panicking.
+    /// # Errors
+    /// Returns `Error::TooFewNonZeroWeights` if `weights` has less than two non-zero weights, or
+    /// `Error::WeightSumOverflow` if their sum does not fit in the wider of `W` and `usize`.
+    pub fn try_from_weights<W: Weight>(weights: &[W]) -> Result<Self, Error> {
+        Ok(Self::from_layout(Layout::try_new(weights)?, weights))
+    }
+
+    /// Create a new DDG tree from run-length encoded weights, where each `(weight, run_length)`
+    /// pair stands for `run_length` consecutive buckets of weight `weight`. Long runs, such as a
+    /// million items of weight one, are never expanded into a list of weights.
+    /// # Panics
+    /// Will panic if the expanded distribution is invalid for `Generator::new`, or if the number
+    /// of buckets overflows a `usize`.
+    #[must_use]
+    pub fn from_runs(runs: &[(usize, usize)]) -> Self {
+        let overflow = || panic!("{}", Error::WeightSumOverflow);
+        let too_large = || panic!("{}", Error::TreeTooLarge);
+        let (bucket_count, nonzero_count, sum, leaf_count) = runs.iter().fold(
+            (0usize, 0usize, 0usize, 0usize),
+            |(buckets, nonzero, sum, leaves), &(weight, run_length)| {
+                let run_sum = weight.checked_mul(run_length).unwrap_or_else(overflow);
+                let run_leaves = (weight.count_ones() as usize)
+                    .checked_mul(run_length)
+                    .unwrap_or_else(too_large);
+                (
+                    buckets.checked_add(run_length).unwrap_or_else(overflow),
+                    nonzero + if weight > 0 { run_length } else { 0 },
+                    sum.checked_add(run_sum).unwrap_or_else(overflow),
+                    leaves.checked_add(run_leaves).unwrap_or_else(too_large),
+                )
+            },
+        );
+        if nonzero_count < 2 {
+            panic!("{}", Error::TooFewNonZeroWeights);
+        }
+        let layout = Layout::try_from_shape(bucket_count, sum as u128, leaf_count)
+            .unwrap_or_else(|e| panic!("{e}"));
+        Self::with_matrix(layout, |layout, matrix| {
+            layout.fill_from(
+                || {
+                    runs.iter()
+                        .flat_map(|&(weight, run_length)| core::iter::repeat_n(weight, run_length))
+                },
+                matrix,
+            );
+        })
+    }
+
+    /// Create a new DDG tree for `n` equally likely outcomes, equivalent to `Generator::new` with
+    /// `n` weights of one but without allocating or scanning the weights.
+    /// # Panics
+    /// Will panic if `n` is less than two, or if the level-label matrix would not be addressable.
+    #[must_use]
+    pub fn new_uniform(n: usize) -> Self {
+        if n < 2 {
+            panic!("{}", Error::TooFewNonZeroWeights);
+        }
+        let layout = Layout::try_from_shape(n, n as u128, n).unwrap_or_else(|e| panic!("{e}"));
+        Self::with_matrix(layout, Layout::fill_uniform)
+    }
+
+    /// Adopt a packed level-label matrix that was built elsewhere, e.g. deserialized, after
+    /// checking that sampling it can never leave its bounds and terminates with probability one.
+    /// The label `bucket_count` is the padding label, which may appear in any level but the first,
+    /// since the constructors keep the padding weight below half of the tree. As with `try_new`,
+    /// at least two buckets must have a non-zero weight.
+    pub(crate) fn try_from_matrix(
+        bucket_count: usize,
+        level_label_matrix: Arc<[usize]>,
+    ) -> Result<Self, Error> {
+        let malformed = Err(Error::MalformedTree);
+        let Some(adjusted_bucket_count) = bucket_count.checked_add(1) else {
+            return malformed;
+        };
+        if level_label_matrix.is_empty() {
+            return malformed;
+        }
+
+        // A level with more open nodes than twice the labels it can hold never closes.
+        let Some(max_open) = adjusted_bucket_count
+            .checked_mul(2)
+            .and_then(|n| n.checked_add(2))
+        else {
+            return malformed;
+        };
+
+        // The root has two children. Every node of a level that is not a leaf has two children
+        // in the next level, and every node of the last level must be a leaf, so that the
+        // traversal never runs past the last level.
+        let mut open: usize = 2;
+        let mut first_bucket = None;
+        let mut has_two_buckets = false;
+        let mut depth = 0;
+        let mut rest = &*level_label_matrix;
+        while let Some((&count, tail)) = rest.split_first() {
+            // Each level holds one bit of the weights, which are recovered as `u128`s.
+            depth += 1;
+            if depth > u128::BITS {
+                return malformed;
+            }
+            if count > open || count > adjusted_bucket_count || count > tail.len() {
+                return malformed;
+            }
+            let (labels, tail) = tail.split_at(count);
+            if labels.iter().any(|&label| label >= adjusted_bucket_count) {
+                return malformed;
+            }
+
+            // Every level is built in increasing order of label, which `set_weight` relies on.
+            if labels.windows(2).any(|pair| pair[0] >= pair[1]) {
+                return malformed;
+            }
+
+            // A padding weight of at least half of the tree has a leaf on the first level.
+            if depth == 1 && labels.last() == Some(&bucket_count) {
+                return malformed;
+            }
+            for &label in labels.iter().filter(|&&label| label < bucket_count) {
+                has_two_buckets |= *first_bucket.get_or_insert(label) != label;
+            }
+            rest = tail;
+
+            open = match (open - count).checked_mul(2) {
+                Some(open) if open <= max_open => open,
+                _ => return malformed,
+            };
+        }
+        if open != 0 {
+            return malformed;
+        }
+        if !has_two_buckets {
+            return Err(Error::TooFewNonZeroWeights);
+        }
+
+        Ok(Self {
+            bucket_count,
+            level_label_matrix,
+        })
+    }
+
+    /// Create a new generator that samples from this distribution conditioned on the sampled index
+    /// falling within `range`. The new tree is built exactly from the renormalized weights of the
+    /// buckets in `range`, and sampled items are still indices into the initial distribution.
+    /// # Panics
+    /// Will panic if `range` contains less than two buckets with a non-zero weight.
+    #[must_use]
+    pub fn restrict(&self, range: impl RangeBounds<usize>) -> Self {
+        let mut weights = self.weights();
+        for (i, w) in weights.iter_mut().enumerate() {
+            if !range.contains(&i) {
+                *w = 0;
+            }
+        }
+        Self::new(&weights)
+    }
+}
+
+/// The number of draws `Generator::sample_masked` rejects before it renormalizes the allowed
+/// weights.
+const MASKED_ATTEMPTS: usize = 32;
+
+impl<L: Label> Generator<L> {
+    /// Allocate and populate the DDG tree for a distribution that has already been validated.
+    pub(crate) fn from_layout<W: Weight>(layout: Layout, distribution: &[W]) -> Self {
+        Self::with_matrix(layout, |layout, matrix| layout.fill(distribution, matrix))
+    }
+
+    /// Allocate a zeroed, packed level-label matrix for `layout` and populate it with `fill`.
+    pub(crate) fn with_matrix(layout: Layout, fill: impl FnOnce(&Layout, &mut [L])) -> Self {
+        // Allocate the shared matrix directly rather than building a `Vec` and copying it.
+        let mut level_label_matrix: Arc<[L]> =
+            core::iter::repeat_n(L::default(), layout.matrix_len()).collect();
+        fill(
+            &layout,
+            Arc::get_mut(&mut level_label_matrix).expect("The matrix was just allocated."),
+        );
+
+        Self {
+            bucket_count: layout.bucket_count,
+            level_label_matrix,
+        }
+    }
+
+    /// The depth of the DDG tree, i.e. the ceiling of the base 2 logarithm of the sum of weights.
+    /// Every pass from the root flips the coin at most this many times.
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        levels(&self.level_label_matrix).count()
+    }
+
+    /// Recover the weights of the input distribution from the DDG tree.
+    /// Each level of the tree holds one bit of the binary expansion of every weight, so the
+    /// original integer weights are reconstructed exactly.
+    /// # Panics
+    /// Will panic if a weight does not fit in a `usize`, which is only possible for a generator
+    /// built from wider weights by `Generator::from_weights`.
+    #[must_use]
+    pub fn weights(&self) -> Vec<usize> {
+        self.wide_weights()
+            .into_iter()
+            .map(|w| usize::try_from(w).expect("Every weight must fit in a `usize`."))
+            .collect()
+    }
+
+    /// Recover the weights of the input distribution at the widest supported width.
+    fn wide_weights(&self) -> Vec<u128> {
+        let depth = self.depth();
+        let mut weights = vec![0; self.bucket_count];
+        for (j, labels) in levels(&self.level_label_matrix).enumerate() {
+            for label in labels.iter().map(|&label| label.to_usize()) {
+                if label < self.bucket_count {
+                    weights[label] |= 1u128
+                        .checked_shl((depth - j - 1) as u32)
+                        .expect("Every weight must fit in a `usize`.");
+                }
+            }
+        }
+        weights
+    }
+
+    /// Whether every bucket of the input distribution has the same weight.
+    #[must_use]
+    pub fn is_uniform(&self) -> bool {
+        let weights = self.weights();
+        weights.iter().all(|&w| w == weights[0])
+    }
+
+    /// The index of the most likely bucket, preferring the smallest index on ties.
+    #[must_use]
+    pub fn argmax(&self) -> usize {
+        self.weights()
+            .iter()
+            .enumerate()
+            .max_by(|(i, a), (j, b)| a.cmp(b).then(j.cmp(i)))
+            .map_or(0, |(i, _)| i)
+    }
+
+    /// The smallest non-zero probability of any bucket.
+    #[must_use]
+    pub fn min_nonzero_probability(&self) -> f64 {
+        let weights = self.weights();
+        let min = weights
+            .iter()
+            .copied()
+            .filter(|&w| w > 0)
+            .min()
+            .unwrap_or(0);
+        min as f64 / weights.iter().sum::<usize>() as f64
+    }
+
+    /// The indices of the buckets with a non-zero weight, in increasing order.
+    #[must_use]
+    pub fn support(&self) -> Vec<usize> {
+        self.weights()
+            .iter()
+            .enumerate()
+            .filter(|(_, &w)| w > 0)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Sample a random item from the discrete distribution using a given `FairCoin`.
+    /// The item is returned as an index into the initial input distribution.
+    pub fn sample(&self, fair_coin: &mut impl FairCoin) -> usize {
+        sample_matrix(&self.level_label_matrix, self.bucket_count, fair_coin)
+    }
+
+    /// Sample a random item like `Generator::sample`, and also return exactly how many coin flips
+    /// the sample consumed, saturating at `u32::MAX`.
+    pub fn sample_counting(&self, fair_coin: &mut impl FairCoin) -> (usize, u32) {
+        let mut coin = coin::CountingCoin::new(fair_coin);
+        let i = self.sample(&mut coin);
+        (i, u32::try_from(coin.flips()).unwrap_or(u32::MAX))
+    }
+
+    /// An endless iterator of random items sampled with the given `FairCoin`, for use with iterator
+    /// combinators such as `take`, `filter`, and `zip`.
+    pub fn sample_iter<'a, C: FairCoin>(
+        &'a self,
+        fair_coin: &'a mut C,
+    ) -> impl Iterator<Item = usize> + 'a {
+        core::iter::repeat_with(move || self.sample(fair_coin))
+    }
+
+    /// Fill `out` with random items sampled with the given `FairCoin`. The tree and its shape are
+    /// loaded once for the whole slice, which makes this the cheapest way to take many samples.
+    pub fn sample_fill(&self, fair_coin: &mut impl FairCoin, out: &mut [usize]) {
+        let (level_label_matrix, bucket_count) = (&*self.level_label_matrix, self.bucket_count);
+        for sample in out {
+            *sample = sample_matrix(level_label_matrix, bucket_count, fair_coin);
+        }
+    }
+
+    /// Sample `n` random items into a new vector, as by `Generator::sample_fill`.
+    pub fn sample_n(&self, fair_coin: &mut impl FairCoin, n: usize) -> Vec<usize> {
+        let mut samples = vec![0; n];
+        self.sample_fill(fair_coin, &mut samples);
+        samples
+    }
+
+    /// Sample `n` random items and count how many times each index was sampled.
+    pub fn sample_histogram(&self, fair_coin: &mut impl FairCoin, n: usize) -> Histogram {
+        let mut histogram = Histogram::new(self.bucket_count);
+        for _ in 0..n {
+            histogram.record(self.sample(fair_coin));
+        }
+        histogram
+    }
+
+    /// Sample `n` random items and return them in nondecreasing order, e.g. to build an empirical
+    /// CDF. The counts of each index are drawn with `Generator::sample_multinomial` and then
+    /// expanded, so a large `n` needs far fewer coin flips and tree traversals than sampling every
+    /// item, and no sorting.
+    pub fn sample_n_sorted(&self, fair_coin: &mut impl FairCoin, n: usize) -> Vec<usize> {
+        let mut samples = Vec::with_capacity(n);
+        for (i, count) in self
+            .sample_multinomial(fair_coin, n)
+            .into_iter()
+            .enumerate()
+        {
+            samples.extend(core::iter::repeat_n(i, count));
+        }
+        samples
+    }
+
+    /// Sample a random item as if the weight of each index `i` were multiplied by
+    /// `numerator / denominator`, where `(numerator, denominator) = multiplier(i)`, without
+    /// rebuilding the tree. Each draw is accepted with exactly its multiplier's probability, or
+    /// redrawn otherwise, so the expected number of draws is the inverse of the adjusted fraction
+    /// of the total weight. Multipliers of one cost no extra coin flips.
+    ///
+    /// The sampler never terminates if every index with a non-zero weight has a multiplier of zero.
+    /// # Panics
+    /// Will panic if a multiplier is not in `[0, 1]`.
+    pub fn sample_adjusted(
+        &self,
+        fair_coin: &mut impl FairCoin,
+        multiplier: impl Fn(usize) -> (usize, usize),
+    ) -> usize {
+        loop {
+            let i = self.sample(fair_coin);
+            let (numerator, denominator) = multiplier(i);
+            assert!(
+                numerator <= denominator && denominator > 0,
+                "Every multiplier must be in [0, 1]."
+            );
+            if numerator == denominator
+                || (numerator > 0
+                    && uniform::Uniform::new(denominator).sample(fair_coin) < numerator)
+            {
+                return i;
+            }
+        }
+    }
+
+    /// Sample a random item conditioned on its index being allowed, i.e. with probability
+    /// proportional to its weight among the indices `i` for which `allowed[i]` is `true`, e.g. to
+    /// restrict the next token of a constrained generator. Draws from the full tree are rejected
+    /// until one is allowed, and after `MASKED_ATTEMPTS` rejections the allowed weights are
+    /// renormalized into a temporary generator instead, so a mask that allows only a tiny fraction
+    /// of the weight still terminates promptly. Either way the result is exactly conditional.
+    /// # Panics
+    /// Will panic if `allowed` is not as long as the distribution, or if it allows no index with a
+    /// non-zero weight.
+    pub fn sample_masked(&self, fair_coin: &mut impl FairCoin, allowed: &[bool]) -> usize {
+        assert_eq!(
+            allowed.len(),
+            self.bucket_count,
+            "The mask must have one entry for each bucket."
         );
-        let bucket_count = distribution.len();
-        let sum: usize = distribution.iter().sum();
+        for _ in 0..MASKED_ATTEMPTS {
+            let i = self.sample(fair_coin);
+            if allowed[i] {
+                return i;
+            }
+        }
+
+        // Every attempt was rejected, so the allowed weight is likely small: sample it directly.
+        let masked: Vec<_> = self
+            .weights()
+            .into_iter()
+            .zip(allowed)
+            .map(|(weight, &allowed)| if allowed { weight } else { 0 })
+            .collect();
+        choice::Choice::new(&masked)
+            .expect("The mask must allow an index with a non-zero weight.")
+            .sample(fair_coin)
+    }
+
+    /// Sample a random item like `Generator::sample`, but without any operation that may panic.
+    /// Every index into the tree is bounds-checked and all arithmetic is checked, so this method
+    /// can be relied upon to never abort the calling process.
+    /// # Errors
+    /// Returns `Error::MalformedTree` if the traversal leaves the bounds of the tree. This cannot
+    /// happen for a tree built by this crate.
+    #[cfg(feature = "strict")]
+    pub fn try_sample(&self, fair_coin: &mut impl FairCoin) -> Result<usize, Error> {
+        let mut label_index: usize = 0;
+        let mut row_start: usize = 0;
+
+        loop {
+            let toss = fair_coin.flip();
+            label_index = label_index
+                .checked_mul(2)
+                .and_then(|i| i.checked_add(usize::from(toss)))
+                .ok_or(Error::MalformedTree)?;
+
+            let leaf_count = self
+                .level_label_matrix
+                .get(row_start)
+                .ok_or(Error::MalformedTree)?
+                .to_usize();
+
+            if label_index < leaf_count {
+                let j = label_index
+                    .checked_add(row_start)
+                    .and_then(|i| i.checked_add(1))
+                    .and_then(|i| self.level_label_matrix.get(i))
+                    .ok_or(Error::MalformedTree)?
+                    .to_usize();
+                if j < self.bucket_count {
+                    return Ok(j);
+                }
+
+                label_index = 0;
+                row_start = 0;
+            } else {
+                label_index -= leaf_count;
+                row_start = row_start
+                    .checked_add(leaf_count)
+                    .and_then(|i| i.checked_add(1))
+                    .ok_or(Error::MalformedTree)?;
+            }
+        }
+    }
+}
+
+/// The shape of a DDG tree, computed from a distribution before any matrix memory is allocated.
+/// Separating the shape from the storage lets callers, such as `pool::GeneratorPool`, decide where
+/// the level-label matrix lives.
+#[derive(Clone, Copy)]
+pub(crate) struct Layout {
+    pub(crate) bucket_count: usize,
+    pub(crate) adjusted_bucket_count: usize,
+    depth: usize,
+    leaf_count: usize,
+    padding: u128,
+}
+
+impl Layout {
+    /// Determine the shape of the DDG tree for the given distribution.
+    /// # Panics
+    /// Will panic if `distribution` has less than two non-zero weights, or if the sum of the
+    /// weights overflows a `usize`.
+    pub(crate) fn new<W: Weight>(distribution: &[W]) -> Self {
+        Self::try_new(distribution).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Determine the shape of the DDG tree for the given distribution, validating that the tree
+    /// can be represented. Once a `Layout` exists, all arithmetic used to fill its matrix is
+    /// bounded by `matrix_len` and cannot overflow.
+    ///
+    /// The sum of the weights must fit in the wider of `W` and `usize`, so that `usize` weights
+    /// keep their limit on every target while wider weights are not truncated.
+    pub(crate) fn try_new<W: Weight>(distribution: &[W]) -> Result<Self, Error> {
+        if distribution.iter().filter(|w| w.is_nonzero()).count() < 2 {
+            return Err(Error::TooFewNonZeroWeights);
+        }
+        let sum_bits = W::BITS.max(usize::BITS);
+        let sum = distribution
+            .iter()
+            .try_fold(0u128, |sum, w| sum.checked_add(w.to_u128()))
+            .filter(|sum| sum.checked_shr(sum_bits).unwrap_or(0) == 0)
+            .ok_or(Error::WeightSumOverflow)?;
+        let leaf_count = distribution
+            .iter()
+            .try_fold(0usize, |leaves, w| {
+                leaves.checked_add(w.to_u128().count_ones() as usize)
+            })
+            .ok_or(Error::TreeTooLarge)?;
+        Self::try_from_shape(distribution.len(), sum, leaf_count)
+    }
+
+    /// Determine the shape of the DDG tree for `bucket_count` weights with a total of `sum` and
+    /// `leaf_count` set bits across their binary expansions, validating that the tree can be
+    /// represented.
+    pub(crate) fn try_from_shape(
+        bucket_count: usize,
+        sum: u128,
+        leaf_count: usize,
+    ) -> Result<Self, Error> {
         let is_power_of_two = sum.is_power_of_two();
 
         // Get the ceiling of the base 2 logarithm of `sum`.
@@ -84,34 +728,131 @@ impl Generator {
         // still being able to represent the sum of the weights.
         let depth: usize = sum.ilog2() as usize + usize::from(!is_power_of_two);
 
-        let a: Vec<_> = if is_power_of_two {
-            // Copy the existing distribution to owned memory.
-            distribution.to_vec()
-        } else {
-            // Append an element to the distribution to make the new sum a power of two.
-            // As we'll see, this is crucial to utilizing unsigned integer arithmetic to build our
-            // DDG tree.
-            (0..=bucket_count)
-                .map(|i| {
-                    if i < bucket_count {
-                        distribution[i]
-                    } else {
-                        (1 << depth) - sum
-                    }
-                })
-                .collect()
-        };
+        // When `depth` equals the bit width of `u128`, the power of two itself is not
+        // representable, but the difference always is. Wrapping arithmetic computes it exactly in
+        // both cases.
+        let padding = 1u128
+            .checked_shl(depth as u32)
+            .unwrap_or(0)
+            .wrapping_sub(sum);
+
+        let leaf_count = leaf_count
+            .checked_add(padding.count_ones() as usize)
+            .ok_or(Error::TreeTooLarge)?;
+
+        Ok(Self {
+            padding,
+            ..Self::try_from_depth(bucket_count, depth, !is_power_of_two, leaf_count)?
+        })
+    }
+
+    /// Determine the shape of a DDG tree with `depth` levels and `leaf_count` leaves in total,
+    /// whose buckets are followed by a padding label if `padded`, validating that the tree can be
+    /// represented. The padding weight is left at zero, so the caller must fill the matrix itself
+    /// unless it sets it.
+    pub(crate) fn try_from_depth(
+        bucket_count: usize,
+        depth: usize,
+        padded: bool,
+        leaf_count: usize,
+    ) -> Result<Self, Error> {
+        // Ensure the matrix size, including the leaf count of every level, is addressable.
+        let adjusted_bucket_count = bucket_count
+            .checked_add(usize::from(padded))
+            .ok_or(Error::TreeTooLarge)?;
+        depth.checked_add(leaf_count).ok_or(Error::TreeTooLarge)?;
+
+        Ok(Self {
+            bucket_count,
+            adjusted_bucket_count,
+            depth,
+            leaf_count,
+            padding: 0,
+        })
+    }
+
+    /// The number of `usize` entries required to store the packed level-label matrix: one leaf
+    /// count per level and one label per leaf.
+    pub(crate) fn matrix_len(&self) -> usize {
+        self.depth + self.leaf_count
+    }
+
+    /// The padding weight appended to make the sum a power of two, if the sum is not one already.
+    fn padding(&self) -> Option<u128> {
+        (self.adjusted_bucket_count != self.bucket_count).then_some(self.padding)
+    }
+
+    /// Populate the zeroed `level_label_matrix` with the DDG tree of `bucket_count` weights of one.
+    /// Every bucket is a leaf of the last level, and only the padding label appears above it.
+    pub(crate) fn fill_uniform<L: Label>(&self, level_label_matrix: &mut [L]) {
+        debug_assert_eq!(level_label_matrix.len(), self.matrix_len());
 
-        // Create a matrix to store the labels that occur within each level of the tree,
-        // as well as the number of labels in that level.
-        // TODO: Try to store this matrix in a sparse representation to save space.
-        // However, data locality is important for performance, so we'll need to be careful.
-        let mut level_label_matrix = vec![0; (a.len() + 1) * depth];
+        #[cfg(feature = "metrics")]
+        let _timer = metrics::BuildTimer::start();
+
+        let padding = self.padding().unwrap_or(0);
+        let mut row_start = 0;
+        for j in 0..self.depth {
+            let row = &mut level_label_matrix[row_start..];
+            let mut count = 0;
+            if j == self.depth - 1 {
+                for (label, entry) in row[1..=self.bucket_count].iter_mut().enumerate() {
+                    *entry = L::from_usize(label);
+                }
+                count = self.bucket_count;
+            }
+            if (padding >> (self.depth - j - 1)) & 1 > 0 {
+                count += 1;
+                row[count] = L::from_usize(self.bucket_count);
+            }
+            row[0] = L::from_usize(count);
+            row_start += count + 1;
+        }
+    }
+
+    /// Populate the zeroed `level_label_matrix` with the DDG tree of `distribution`.
+    /// `level_label_matrix` must have a length of exactly `self.matrix_len()`.
+    pub(crate) fn fill<W: Weight, L: Label>(
+        &self,
+        distribution: &[W],
+        level_label_matrix: &mut [L],
+    ) {
+        self.fill_from(|| distribution.iter().copied(), level_label_matrix);
+    }
+
+    /// Populate the zeroed `level_label_matrix` with the DDG tree of the weights yielded by
+    /// `distribution`, which is iterated once per level of the tree. Each level is written as its
+    /// leaf count followed by its labels, directly after the previous level.
+    pub(crate) fn fill_from<W: Weight, I: Iterator<Item = W>, L: Label>(
+        &self,
+        distribution: impl Fn() -> I,
+        level_label_matrix: &mut [L],
+    ) {
+        let depth = self.depth;
+        debug_assert_eq!(level_label_matrix.len(), self.matrix_len());
+
+        #[cfg(feature = "metrics")]
+        let _timer = metrics::BuildTimer::start();
+
+        // Append an implicit element to the distribution to make the new sum a power of two.
+        // As we'll see, this is crucial to utilizing unsigned integer arithmetic to build our
+        // DDG tree. The padded distribution is only ever iterated, so it is never materialized.
+        // The sum, and so the padding, may be wider than the weights, so every weight is widened.
+        let padding = self.padding();
+        let a = || distribution().map(Weight::to_u128).chain(padding);
+
+        // The matrix stores the labels that occur within each level of the tree,
+        // as well as the number of labels in that level. Levels are packed back-to-back, so a
+        // level only takes as much space as it has leaves, and `row_start` tracks where the
+        // current level begins.
+        let mut row_start = 0;
 
         // Iterate over the levels of the DDG tree and populate them with the appropriate entries.
         for j in 0..depth {
+            let mut count = 0;
+
             // Iterate over the labels in the (possibly appended) distribution.
-            for (i, &w) in a.iter().enumerate() {
+            for (i, w) in a().enumerate() {
                 // Use the binary expansion of the weight for label `i` to determine the locations
                 // of this label in the tree. The sum has been adjusted to be a power of two, so
                 // when dividing each weight by the total to get the probability, the action
@@ -126,102 +867,335 @@ impl Generator {
                 // closer to the root in the tree, thus more likely to be sampled, and will have
                 // more leaves assigned their label based on their hamming weight.
                 if (w >> (depth - j - 1)) & 1 > 0 {
-                    // Use `k` to index into the start of the level in the matrix.
-                    let k = j * (a.len() + 1);
-
                     // Increase the number of labels in the current level.
-                    let count = {
-                        level_label_matrix[k] += 1;
-                        level_label_matrix[k]
-                    };
+                    count += 1;
 
                     // Add the label to the current level.
-                    level_label_matrix[k + count] = i;
+                    level_label_matrix[row_start + count] = L::from_usize(i);
                 }
             }
+
+            // Record the number of labels, and begin the next level right after the last of them.
+            level_label_matrix[row_start] = L::from_usize(count);
+            row_start += count + 1;
         }
+    }
 
-        Self {
-            bucket_count,
-            adjusted_bucket_count: a.len(),
-            level_label_matrix,
+    /// Populate the zeroed `level_label_matrix` from the binary expansions of weights that are
+    /// too wide for `fill_from`, where `bit(i, k)` is bit `k` of the weight of label `i`, and the
+    /// label `bucket_count` is the padding label if the layout is padded.
+    #[cfg(feature = "num-bigint")]
+    pub(crate) fn fill_bits<L: Label>(
+        &self,
+        bit: impl Fn(usize, usize) -> bool,
+        level_label_matrix: &mut [L],
+    ) {
+        debug_assert_eq!(level_label_matrix.len(), self.matrix_len());
+
+        #[cfg(feature = "metrics")]
+        let _timer = metrics::BuildTimer::start();
+
+        let mut row_start = 0;
+        for j in 0..self.depth {
+            let mut count = 0;
+            for i in 0..self.adjusted_bucket_count {
+                if bit(i, self.depth - j - 1) {
+                    count += 1;
+                    level_label_matrix[row_start + count] = L::from_usize(i);
+                }
+            }
+            level_label_matrix[row_start] = L::from_usize(count);
+            row_start += count + 1;
         }
     }
+}
 
-    /// Sample a random item from the discrete distribution using a given `FairCoin`.
-    /// The item is returned as an index into the initial input distribution.
-    pub fn sample(&self, fair_coin: &mut impl FairCoin) -> usize {
-        let mut label_index = 0;
-        let mut level = 0;
+/// Iterate over the labels of each level of a packed level-label matrix, from the root down.
+pub(crate) fn levels<L: Label>(level_label_matrix: &[L]) -> impl Iterator<Item = &[L]> {
+    let mut rest = level_label_matrix;
+    core::iter::from_fn(move || {
+        let (&count, tail) = rest.split_first()?;
+        let (labels, tail) = tail.split_at(count.to_usize());
+        rest = tail;
+        Some(labels)
+    })
+}
 
-        // Traverse the binary tree with coin flips until a leaf is reached.
-        loop {
-            // Flip a fair coin for random sample outputs.
-            let toss = fair_coin.flip();
+/// Sample a random item by traversing the DDG tree stored in `level_label_matrix`.
+/// Labels greater than or equal to `bucket_count` are the appended padding label and trigger a
+/// back-edge to the root.
+pub(crate) fn sample_matrix<L: Label>(
+    level_label_matrix: &[L],
+    bucket_count: usize,
+    fair_coin: &mut impl FairCoin,
+) -> usize {
+    #[cfg(feature = "metrics")]
+    {
+        let mut counting = coin::CountingCoin::new(fair_coin);
+        let j = traverse_matrix(level_label_matrix, bucket_count, &mut counting);
+        metrics::record_sample(counting.flips());
+        j
+    }
 
-            // Bit shift the index and add the coin toss to choose a random child in the tree.
-            label_index = (label_index << 1) + usize::from(toss);
+    #[cfg(not(feature = "metrics"))]
+    traverse_matrix(level_label_matrix, bucket_count, fair_coin)
+}
 
-            // Use `k` to index into the start of the level in the matrix.
-            let k = level * (self.adjusted_bucket_count + 1);
+/// The FLDR sampling loop shared by every sampler in the crate.
+fn traverse_matrix<L: Label>(
+    level_label_matrix: &[L],
+    bucket_count: usize,
+    fair_coin: &mut impl FairCoin,
+) -> usize {
+    // Each level of the matrix is a row of its leaf count followed by exactly that many labels,
+    // so the next row begins right after the last label of the current one.
+    let mut row_start = 0;
+    let mut label_index = 0;
 
-            // Check the index is within the current tree level.
-            if label_index < self.level_label_matrix[k] {
-                // Check the label here is within the actual distribution and is not the appended value.
-                let j = self.level_label_matrix[k + label_index + 1];
-                if j < self.bucket_count {
-                    // Return the sampled label.
-                    return j;
-                }
+    // Traverse the binary tree with coin flips until a leaf is reached.
+    loop {
+        // Flip a fair coin for random sample outputs.
+        let toss = fair_coin.flip();
 
-                // Take a back-edge to the root of the tree/graph.
-                label_index = 0;
-                level = 0;
-            } else {
-                // Wrap the label index by the level's leaf count.
-                label_index -= self.level_label_matrix[k];
+        // Bit shift the index and add the coin toss to choose a random child in the tree.
+        label_index = (label_index << 1) + usize::from(toss);
 
-                // Increase to the next level in the tree.
-                level += 1;
+        // Check the index is within the current tree level.
+        let leaf_count = level_label_matrix[row_start].to_usize();
+        if label_index < leaf_count {
+            // Check the label here is within the actual distribution and is not the appended value.
+            let j = level_label_matrix[row_start + label_index + 1].to_usize();
+            if j < bucket_count {
+                // Return the sampled label.
+                return j;
             }
+
+            // Take a back-edge to the root of the tree/graph.
+            label_index = 0;
+            row_start = 0;
+        } else {
+            // Wrap the label index by the level's leaf count.
+            label_index -= leaf_count;
+
+            // Move to the row of the next level in the tree.
+            row_start += leaf_count + 1;
         }
     }
 }
 
+pub mod actions;
+#[cfg(feature = "std")]
+pub mod annealing;
+pub mod anti_repeat;
+#[cfg(feature = "std")]
+pub mod approximate;
+#[cfg(feature = "verifiable")]
+pub mod beacon;
+#[cfg(feature = "num-bigint")]
+pub mod bigint;
+pub mod builder;
+pub mod chaos;
+mod choice;
+pub mod codegen;
+pub mod coin;
+#[cfg(feature = "rand_core")]
+pub mod constant_time;
+mod convert;
+#[cfg(feature = "std")]
+pub mod discrete_gaussian;
+#[cfg(feature = "std")]
+pub mod distance;
+#[cfg(feature = "std")]
+pub mod distributions;
+mod dot;
+pub mod dynamic;
+pub mod encoding;
+pub mod enums;
+#[cfg(feature = "std")]
+pub mod estimate;
+mod fenwick;
+pub mod grammar;
+pub mod graph;
+pub mod grid;
+#[cfg(feature = "harness")]
+pub mod harness;
+pub mod histogram;
+pub mod label;
+pub mod labeled;
+pub mod markov;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod mixture;
+mod multinomial;
+#[cfg(feature = "std")]
+pub mod partitioned;
+pub mod pool;
+pub mod probability;
+pub mod procgen;
+#[cfg(feature = "verifiable")]
+pub mod provably_fair;
+pub mod ranking;
+mod reference;
+pub mod roller;
+pub mod round_robin;
+pub mod sampler;
+#[cfg(feature = "serde")]
+mod serialization;
+pub mod shuffle_bag;
+pub mod sparse;
+pub mod split;
+mod stats;
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+pub mod testing;
+pub mod uniform;
+mod update;
+pub mod weight;
+
 #[cfg(feature = "rand")]
 pub mod rand {
-    use rand::{rngs::ThreadRng, Rng};
+    #[cfg(feature = "std")]
+    use std::{cell::RefCell, collections::HashMap};
+
+    #[cfg(feature = "std")]
+    use rand::rngs::ThreadRng;
+    use rand::{distributions::Distribution, Rng};
+
+    pub use crate::rand_core::RngCoin;
+    use crate::{label::Label, Generator};
+
+    /// The number of distributions each thread keeps generators for in `sample_weighted`.
+    #[cfg(feature = "std")]
+    const CACHE_CAPACITY: usize = 64;
+
+    #[cfg(feature = "std")]
+    thread_local! {
+        static COIN: RefCell<RngCoin<ThreadRng>> = RefCell::new(RngCoin::default());
+        static CACHE: RefCell<HashMap<Box<[usize]>, Generator>> = RefCell::new(HashMap::new());
+    }
+
+    /// Sample a random index of `distribution` with a thread-local coin, without managing a
+    /// `Generator` or coin.
+    ///
+    /// Each thread keeps the generators of the distributions it sampled most recently, keyed by
+    /// their weights, so repeated calls with the same weights only pay for the tree once. Prefer
+    /// holding a `Generator` when the distribution is known ahead of time.
+    /// # Panics
+    /// Will panic if `distribution` is invalid for `Generator::new`.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn sample_weighted(distribution: &[usize]) -> usize {
+        let generator = CACHE.with_borrow_mut(|cache| {
+            if let Some(generator) = cache.get(distribution) {
+                return generator.clone();
+            }
+            let generator = Generator::new(distribution);
+            if cache.len() >= CACHE_CAPACITY {
+                cache.clear();
+            }
+            cache.insert(distribution.into(), generator.clone());
+            generator
+        });
+        COIN.with_borrow_mut(|coin| generator.sample(coin))
+    }
+
+    /// Sample a generator directly with any `Rng`, e.g. `rng.sample(&generator)` or
+    /// `Distribution::sample(&generator, &mut rng)`, so it can stand in for `WeightedIndex`.
+    ///
+    /// Each sample builds a fresh `RngCoin` over the RNG, discarding the bits it did not use, so
+    /// holding an `RngCoin` is more economical when sampling many times.
+    impl<L: Label> Distribution<usize> for Generator<L> {
+        fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+            Generator::sample(self, &mut RngCoin::new(rng))
+        }
+    }
+
+    /// Create a new `RngCoin` and default to using the local `ThreadRng` instance RNG.
+    #[cfg(feature = "std")]
+    impl Default for RngCoin<ThreadRng> {
+        fn default() -> Self {
+            RngCoin::new(ThreadRng::default())
+        }
+    }
+}
+
+/// Coins over any `rand_core::RngCore`, for users who have an RNG but not the full `rand` crate.
+#[cfg(feature = "rand_core")]
+pub mod rand_core {
+    use rand_core::{RngCore, SeedableRng};
+
+    use crate::coin::BitOrder;
 
     /// Helper type for performing repeated coin flips.
     /// Fetches random bits from a given RNG in blocks of 64 bits and return them one at a time.
-    pub struct RngCoin<R: Rng> {
+    ///
+    /// With the `serde` feature, the coin is serializable whenever its RNG is, including the
+    /// buffered bits, so a checkpointed coin resumes the exact same stream of flips.
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct RngCoin<R: RngCore> {
         rng: R,
         random_bits: u64,
         bits_read: u32,
+        #[cfg_attr(feature = "serde", serde(default))]
+        bit_order: BitOrder,
     }
 
-    impl<R: Rng> RngCoin<R> {
+    impl<R: RngCore> RngCoin<R> {
         /// Create a new `RngCoin` instance with the given RNG and assign a random `u64` to `random_bits`.
         #[must_use]
-        pub fn new(mut rng: R) -> Self {
+        pub fn new(rng: R) -> Self {
+            Self::with_bit_order(rng, BitOrder::default())
+        }
+
+        /// Create a new `RngCoin` that consumes the bits of each random `u64` in the given order.
+        #[must_use]
+        pub fn with_bit_order(mut rng: R, bit_order: BitOrder) -> Self {
             let random_bits = rng.next_u64();
             Self {
                 rng,
                 random_bits,
                 bits_read: 0,
+                bit_order,
             }
         }
     }
 
-    /// Create a new `RngCoin` and default to using the local `ThreadRng` instance RNG.
-    impl Default for RngCoin<ThreadRng> {
-        fn default() -> Self {
-            RngCoin::new(ThreadRng::default())
+    impl<R: RngCore + SeedableRng> RngCoin<R> {
+        /// Create a new, independent `RngCoin` whose RNG is seeded from this coin's RNG.
+        /// Forking is deterministic: two coins in the same state produce identical forks.
+        /// # Panics
+        /// Will panic if this coin's RNG fails to produce a seed.
+        #[must_use]
+        pub fn fork(&mut self) -> Self {
+            Self::with_bit_order(
+                R::from_rng(&mut self.rng).expect("The parent RNG failed to produce a seed."),
+                self.bit_order,
+            )
+        }
+    }
+
+    /// With the `zeroize` feature, clear the buffered random bits. The RNG itself is left untouched,
+    /// so it should implement `ZeroizeOnDrop` itself if its state is sensitive.
+    #[cfg(feature = "zeroize")]
+    impl<R: RngCore> zeroize::Zeroize for RngCoin<R> {
+        fn zeroize(&mut self) {
+            self.random_bits.zeroize();
+            // Mark the buffer as consumed so that the next flip draws fresh bits from the RNG.
+            self.bits_read = u64::BITS;
         }
     }
 
+    #[cfg(feature = "zeroize")]
+    impl<R: RngCore> Drop for RngCoin<R> {
+        fn drop(&mut self) {
+            zeroize::Zeroize::zeroize(self);
+        }
+    }
+
+    #[cfg(feature = "zeroize")]
+    impl<R: RngCore + zeroize::ZeroizeOnDrop> zeroize::ZeroizeOnDrop for RngCoin<R> {}
+
     /// Implement the `FairCoin` trait so that this struct can be sampled by the FLDR `Generator`.
-    impl<R: Rng> super::FairCoin for RngCoin<R> {
+    impl<R: RngCore> super::FairCoin for RngCoin<R> {
         fn flip(&mut self) -> bool {
             // If we have read the entire `u64` of random bits, then we need to generate a new block.
             if self.bits_read == u64::BITS {
@@ -229,13 +1203,22 @@ pub mod rand {
                 self.bits_read = 0;
             }
 
-            // Grab the right-most bit and increment the number of bits read.
-            let b = self.random_bits & 1 > 0;
+            // Grab the next bit and increment the number of bits read.
             self.bits_read += 1;
-
-            // Shift the random bits to the right by one and return the result bit.
-            self.random_bits >>= 1;
-            b
+            match self.bit_order {
+                BitOrder::LsbFirst => {
+                    // Take the right-most bit and shift the random bits to the right by one.
+                    let b = self.random_bits & 1 > 0;
+                    self.random_bits >>= 1;
+                    b
+                }
+                BitOrder::MsbFirst => {
+                    // Take the left-most bit and shift the random bits to the left by one.
+                    let b = self.random_bits >> (u64::BITS - 1) > 0;
+                    self.random_bits <<= 1;
+                    b
+                }
+            }
         }
     }
 }
@@ -0,0 +1,204 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Sampling sequences from Markov chains and models, with one DDG tree per state.
+
+use alloc::vec::Vec;
+
+use crate::{choice::Choice, FairCoin};
+
+/// Build the choice for one row of a model, panicking with `name` if every weight is zero.
+fn choice(weights: &[usize], name: &str) -> Choice {
+    Choice::new(weights).unwrap_or_else(|| panic!("Every {name} row must have a non-zero weight."))
+}
+
+/// A hidden Markov model, which moves between hidden states and emits one observable symbol from
+/// each state it visits.
+#[derive(Clone)]
+pub struct HiddenMarkovModel {
+    initial: Choice,
+    transitions: Vec<Choice>,
+    emissions: Vec<Choice>,
+    symbol_count: usize,
+}
+
+impl HiddenMarkovModel {
+    /// Create a model from the weights of the initial state, a square matrix of transition weights
+    /// where row `i` holds the weights of moving from state `i` to each state, and a matrix of
+    /// emission weights where row `i` holds the weights of each symbol emitted from state `i`.
+    /// # Panics
+    /// Will panic if the matrices do not have one row per state, if a transition row does not have
+    /// one weight per state, if the emission rows differ in length, or if the initial weights or
+    /// any row are all zero.
+    #[must_use]
+    pub fn new<T: AsRef<[usize]>, E: AsRef<[usize]>>(
+        initial: &[usize],
+        transitions: &[T],
+        emissions: &[E],
+    ) -> Self {
+        let state_count = initial.len();
+        assert!(
+            transitions.len() == state_count && emissions.len() == state_count,
+            "There must be one transition row and one emission row per state."
+        );
+        assert!(
+            transitions
+                .iter()
+                .all(|row| row.as_ref().len() == state_count),
+            "Every transition row must have one weight per state."
+        );
+        let symbol_count = emissions.first().map_or(0, |row| row.as_ref().len());
+        assert!(
+            emissions
+                .iter()
+                .all(|row| row.as_ref().len() == symbol_count),
+            "Every emission row must have the same number of symbols."
+        );
+
+        Self {
+            initial: choice(initial, "initial"),
+            transitions: transitions
+                .iter()
+                .map(|row| choice(row.as_ref(), "transition"))
+                .collect(),
+            emissions: emissions
+                .iter()
+                .map(|row| choice(row.as_ref(), "emission"))
+                .collect(),
+            symbol_count,
+        }
+    }
+
+    /// The number of hidden states.
+    #[must_use]
+    pub fn state_count(&self) -> usize {
+        self.transitions.len()
+    }
+
+    /// The number of observable symbols.
+    #[must_use]
+    pub fn symbol_count(&self) -> usize {
+        self.symbol_count
+    }
+
+    /// Sample a sequence of `len` hidden states and the symbol emitted from each of them, using a
+    /// given `FairCoin`.
+    pub fn sample_sequence(
+        &self,
+        fair_coin: &mut impl FairCoin,
+        len: usize,
+    ) -> (Vec<usize>, Vec<usize>) {
+        let mut states = Vec::with_capacity(len);
+        let mut symbols = Vec::with_capacity(len);
+        let mut state = self.initial.sample(fair_coin);
+        for i in 0..len {
+            if i > 0 {
+                state = self.transitions[state].sample(fair_coin);
+            }
+            states.push(state);
+            symbols.push(self.emissions[state].sample(fair_coin));
+        }
+        (states, symbols)
+    }
+}
+
+/// A Markov chain that samples its next state from the transition row of its current state.
+///
+/// A state is absorbing if its row has no weight, or has weight only on itself. Stepping from an
+/// absorbing state returns it again without flipping the coin.
+#[derive(Clone)]
+pub struct TransitionSampler {
+    transitions: Vec<Option<Choice>>,
+    state: usize,
+}
+
+impl TransitionSampler {
+    /// Create a chain in state `initial` from a square matrix of transition weights, where row `i`
+    /// holds the weights of moving from state `i` to each state.
+    /// # Panics
+    /// Will panic if a row does not have one weight per state, or if `initial` is not a state.
+    #[must_use]
+    pub fn new<T: AsRef<[usize]>>(transitions: &[T], initial: usize) -> Self {
+        let state_count = transitions.len();
+        assert!(
+            transitions
+                .iter()
+                .all(|row| row.as_ref().len() == state_count),
+            "Every transition row must have one weight per state."
+        );
+        assert!(initial < state_count, "The initial state must be a state.");
+
+        Self {
+            transitions: transitions
+                .iter()
+                .enumerate()
+                .map(|(i, row)| match Choice::new(row.as_ref()) {
+                    Some(Choice::Fixed(j)) if j == i => None,
+                    choice => choice,
+                })
+                .collect(),
+            state: initial,
+        }
+    }
+
+    /// The number of states.
+    #[must_use]
+    pub fn state_count(&self) -> usize {
+        self.transitions.len()
+    }
+
+    /// The current state.
+    #[must_use]
+    pub fn state(&self) -> usize {
+        self.state
+    }
+
+    /// Move the chain to `state` without sampling.
+    /// # Panics
+    /// Will panic if `state` is not a state.
+    pub fn set_state(&mut self, state: usize) {
+        assert!(state < self.state_count(), "The state must be a state.");
+        self.state = state;
+    }
+
+    /// Whether the chain can never leave `state` once it has entered it.
+    /// # Panics
+    /// Will panic if `state` is not a state.
+    #[must_use]
+    pub fn is_absorbing(&self, state: usize) -> bool {
+        self.transitions[state].is_none()
+    }
+
+    /// Whether the chain has reached an absorbing state.
+    #[must_use]
+    pub fn is_absorbed(&self) -> bool {
+        self.is_absorbing(self.state)
+    }
+
+    /// Sample the next state, move the chain to it, and return it, using a given `FairCoin`.
+    pub fn step(&mut self, fair_coin: &mut impl FairCoin) -> usize {
+        if let Some(choice) = &self.transitions[self.state] {
+            self.state = choice.sample(fair_coin);
+        }
+        self.state
+    }
+}
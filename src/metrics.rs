@@ -0,0 +1,63 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Operational metrics emitted through the [metrics](https://crates.io/crates/metrics) facade.
+//!
+//! When the `metrics` feature is enabled, every sample and every DDG tree construction is recorded
+//! with the globally installed recorder. No recorder is installed by this crate, so the cost is
+//! negligible until the embedding application installs one.
+
+use std::time::Instant;
+
+/// Counter of the total number of samples taken.
+pub const SAMPLES_TOTAL: &str = "fldr_samples_total";
+
+/// Counter of the total number of coin flips consumed while sampling.
+pub const FLIPS_TOTAL: &str = "fldr_flips_total";
+
+/// Histogram of the number of coin flips consumed by each sample.
+pub const FLIPS_PER_SAMPLE: &str = "fldr_flips_per_sample";
+
+/// Histogram of the time, in seconds, taken to populate each DDG tree.
+pub const BUILD_SECONDS: &str = "fldr_build_seconds";
+
+/// Record a single sample that consumed `flips` coin flips.
+pub(crate) fn record_sample(flips: u64) {
+    metrics::counter!(SAMPLES_TOTAL).increment(1);
+    metrics::counter!(FLIPS_TOTAL).increment(flips);
+    metrics::histogram!(FLIPS_PER_SAMPLE).record(flips as f64);
+}
+
+/// Records the time between its creation and drop as a tree construction.
+pub(crate) struct BuildTimer(Instant);
+
+impl BuildTimer {
+    pub(crate) fn start() -> Self {
+        Self(Instant::now())
+    }
+}
+
+impl Drop for BuildTimer {
+    fn drop(&mut self) {
+        metrics::histogram!(BUILD_SECONDS).record(self.0.elapsed());
+    }
+}
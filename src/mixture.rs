@@ -0,0 +1,109 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Sampling from weighted mixtures of several distributions.
+
+use alloc::vec::Vec;
+
+use crate::{choice::Choice, FairCoin, Generator};
+
+/// Samples from a mixture of component generators, by first choosing a component with
+/// probability proportional to its mixture weight and then sampling an item from that component.
+///
+/// Each component keeps its own DDG tree, so the trees stay as small as the components themselves,
+/// and replacing one component or its weight leaves every other tree untouched.
+#[derive(Clone)]
+pub struct Mixture {
+    components: Vec<Generator>,
+    weights: Vec<usize>,
+    selector: Choice,
+}
+
+impl Mixture {
+    /// Create a mixture of `components`, where component `i` is chosen with probability
+    /// proportional to `weights[i]`.
+    /// # Panics
+    /// Will panic if there is not one weight per component, or if every weight is zero.
+    #[must_use]
+    pub fn new(components: Vec<Generator>, weights: &[usize]) -> Self {
+        assert_eq!(
+            components.len(),
+            weights.len(),
+            "There must be one mixture weight for each component."
+        );
+        Self {
+            components,
+            weights: weights.to_vec(),
+            selector: selector(weights),
+        }
+    }
+
+    /// The component generators.
+    #[must_use]
+    pub fn components(&self) -> &[Generator] {
+        &self.components
+    }
+
+    /// The mixture weight of every component.
+    #[must_use]
+    pub fn weights(&self) -> &[usize] {
+        &self.weights
+    }
+
+    /// Replace component `index` with `component`, keeping its mixture weight.
+    /// # Panics
+    /// Will panic if `index` is out of bounds.
+    pub fn set_component(&mut self, index: usize, component: Generator) {
+        self.components[index] = component;
+    }
+
+    /// Change the mixture weight of component `index`, which rebuilds only the tree choosing
+    /// between the components.
+    /// # Panics
+    /// Will panic if `index` is out of bounds, or if every weight would be zero.
+    pub fn set_weight(&mut self, index: usize, weight: usize) {
+        if self.weights[index] != weight {
+            let mut weights = self.weights.clone();
+            weights[index] = weight;
+            self.selector = selector(&weights);
+            self.weights = weights;
+        }
+    }
+
+    /// Sample a component and then an item from it, returning `(component, item)`, using a given
+    /// `FairCoin`.
+    pub fn sample_component(&self, fair_coin: &mut impl FairCoin) -> (usize, usize) {
+        let component = self.selector.sample(fair_coin);
+        (component, self.components[component].sample(fair_coin))
+    }
+
+    /// Sample an item from the mixture using a given `FairCoin`. The item is an index into the
+    /// distribution of whichever component was chosen.
+    pub fn sample(&self, fair_coin: &mut impl FairCoin) -> usize {
+        self.sample_component(fair_coin).1
+    }
+}
+
+/// Build the choice between components, panicking if every weight is zero.
+fn selector(weights: &[usize]) -> Choice {
+    Choice::new(weights).expect("At least one component must have a non-zero mixture weight.")
+}
@@ -0,0 +1,142 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Counting many i.i.d. draws at once by splitting them across the DDG tree.
+
+use alloc::{vec, vec::Vec};
+
+use crate::{label::Label, levels, uniform::Uniform, FairCoin, Generator};
+
+/// Below this many draws per level-label entry, `Generator::sample_multinomial` simply samples
+/// each draw, since splitting binomials across every node of the tree would cost more.
+const SPLITTING_THRESHOLD: usize = 64;
+
+/// Below this many trials, a binomial is sampled by counting coin flips rather than by rejection.
+const COUNTING_THRESHOLD: usize = 1024;
+
+impl<L: Label> Generator<L> {
+    /// Sample `n` random items and return how many times each index was drawn, i.e. a sample of
+    /// the multinomial distribution with `n` trials over this generator's distribution.
+    ///
+    /// When `n` is small relative to the tree, each draw is sampled and counted. Otherwise the
+    /// draws are split across the DDG tree: every draw that reaches an internal node continues to
+    /// either child with probability one half, so the number that go left is an exact
+    /// `Binomial(m, 1/2)` sample, which is drawn by rejection in `O(sqrt(m) log m)` expected coin
+    /// flips. Draws that reach the padding label restart from the root as a batch. The work is
+    /// then proportional to the number of tree nodes times the square root of the draws through
+    /// them, rather than to `n` itself, and the counts remain exactly multinomial.
+    pub fn sample_multinomial(&self, fair_coin: &mut impl FairCoin, n: usize) -> Vec<usize> {
+        let mut counts = vec![0; self.bucket_count];
+        if n / SPLITTING_THRESHOLD < self.level_label_matrix.len() {
+            for _ in 0..n {
+                counts[self.sample(fair_coin)] += 1;
+            }
+            return counts;
+        }
+
+        let mut walks = Vec::new();
+        let mut next = Vec::new();
+        let mut remaining = n;
+        while remaining > 0 {
+            // The root has two children, which share every draw starting from it.
+            let left = binomial_half(fair_coin, remaining);
+            walks.clear();
+            walks.extend([left, remaining - left]);
+            remaining = 0;
+
+            for labels in levels(&self.level_label_matrix) {
+                // The first nodes of each level are its leaves, and the rest are internal nodes
+                // whose children make up the next level.
+                let (leaves, internal) = walks.split_at(labels.len());
+                for (label, &count) in labels.iter().zip(leaves) {
+                    let j = label.to_usize();
+                    if j < self.bucket_count {
+                        counts[j] += count;
+                    } else {
+                        // Draws that reach the padding label take a back-edge to the root.
+                        remaining += count;
+                    }
+                }
+
+                next.clear();
+                for &count in internal {
+                    let left = binomial_half(fair_coin, count);
+                    next.extend([left, count - left]);
+                }
+                core::mem::swap(&mut walks, &mut next);
+            }
+        }
+        counts
+    }
+}
+
+/// Sample the number of heads in `n` fair coin flips exactly.
+///
+/// Small `n` are sampled by flipping the coin `n` times. Otherwise, for even `n = 2m`, a candidate
+/// `m + d` is drawn uniformly from `0..=n` and accepted with probability
+/// `C(n, m + d) / C(n, m) = prod_{t=1}^{|d|} (m - t + 1) / (m + t)`, which is at most one and is
+/// evaluated as a product of exact Bernoulli trials, so accepted candidates follow the binomial
+/// distribution exactly. An odd `n` adds one flip to a sample for `n - 1`.
+fn binomial_half(fair_coin: &mut impl FairCoin, n: usize) -> usize {
+    if n < COUNTING_THRESHOLD {
+        return (0..n).filter(|_| fair_coin.flip()).count();
+    }
+    if n % 2 == 1 {
+        return usize::from(fair_coin.flip()) + binomial_half(fair_coin, n - 1);
+    }
+
+    let m = n / 2;
+    let candidates = Uniform::new(n + 1);
+    loop {
+        let x = candidates.sample(fair_coin);
+        let d = x.abs_diff(m);
+
+        // Test the smallest factors first, so that distant candidates are rejected early.
+        if (1..=d)
+            .rev()
+            .all(|t| bernoulli(fair_coin, m - t + 1, m + t))
+        {
+            return x;
+        }
+    }
+}
+
+/// Return `true` with probability exactly `numerator / denominator`, by comparing the binary
+/// expansion of the fraction with random bits until they first differ, which takes two flips in
+/// expectation.
+fn bernoulli(fair_coin: &mut impl FairCoin, mut numerator: usize, denominator: usize) -> bool {
+    debug_assert!(numerator <= denominator);
+    loop {
+        // Take the next bit of the expansion, doubling the remainder without overflowing.
+        let complement = denominator - numerator;
+        let bit = numerator >= complement;
+        numerator = if bit {
+            numerator - complement
+        } else {
+            numerator << 1
+        };
+        if fair_coin.flip() != bit {
+            // The random bits fell below the fraction exactly when they drew a zero against a one.
+            return bit;
+        }
+    }
+}
@@ -0,0 +1,141 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Two-stage sampling over domains too large to hold a DDG tree for.
+//!
+//! A `PartitionedGenerator` splits the indices `0..len` into contiguous partitions of about
+//! `sqrt(len)` indices each. A small top-level tree over the total weight of each partition picks a
+//! partition, and a second tree over the weights within that partition picks the index. The
+//! per-partition trees are built on first use and kept in a cache of bounded size, so memory stays
+//! proportional to `sqrt(len)` times the cache capacity no matter how large the domain is. The two
+//! stages together sample each index with exactly its weight divided by the total.
+
+use std::collections::HashMap;
+
+use crate::{choice::Choice, FairCoin};
+
+/// The default number of per-partition trees kept in the cache.
+const DEFAULT_CACHE_CAPACITY: usize = 256;
+
+/// Samples an index in `0..len` whose weight is given by a function rather than a slice.
+pub struct PartitionedGenerator<F: Fn(usize) -> usize> {
+    len: usize,
+    partition_len: usize,
+    weight: F,
+    partitions: Choice,
+    cache: HashMap<usize, (Choice, u64)>,
+    cache_capacity: usize,
+    clock: u64,
+}
+
+impl<F: Fn(usize) -> usize> PartitionedGenerator<F> {
+    /// Create a sampler over `0..len`, where index `i` has weight `weight(i)`. The weight function
+    /// is called once for every index to total the partitions, and again for the indices of a
+    /// partition whenever its tree is rebuilt, so it must be deterministic.
+    /// # Panics
+    /// Will panic if every weight is zero, or if the sum of the weights overflows a `usize`.
+    #[must_use]
+    pub fn new(len: usize, weight: F) -> Self {
+        // The smallest partition length whose square covers the domain.
+        let root = len.isqrt();
+        let partition_len = (root + usize::from(root * root < len)).max(1);
+        let masses: Vec<usize> = (0..len.div_ceil(partition_len))
+            .map(|p| {
+                (p * partition_len..len.min((p + 1) * partition_len))
+                    .try_fold(0usize, |sum, i| sum.checked_add(weight(i)))
+                    .expect("The sum of the weights must fit in a `usize`.")
+            })
+            .collect();
+        masses
+            .iter()
+            .try_fold(0usize, |sum, &m| sum.checked_add(m))
+            .expect("The sum of the weights must fit in a `usize`.");
+
+        Self {
+            len,
+            partition_len,
+            weight,
+            partitions: Choice::new(&masses)
+                .expect("The distribution must have a non-zero weight."),
+            cache: HashMap::new(),
+            cache_capacity: DEFAULT_CACHE_CAPACITY,
+            clock: 0,
+        }
+    }
+
+    /// Keep at most `capacity` per-partition trees, evicting the least recently used. A capacity
+    /// of zero is treated as one.
+    #[must_use]
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache_capacity = capacity.max(1);
+        self
+    }
+
+    /// The number of indices in the domain.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the domain is empty. Always `false`, since some index must have a non-zero weight.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of indices in each partition, except possibly the last.
+    #[must_use]
+    pub fn partition_len(&self) -> usize {
+        self.partition_len
+    }
+
+    /// Sample a random index in `0..len` using a given `FairCoin`.
+    pub fn sample(&mut self, fair_coin: &mut impl FairCoin) -> usize {
+        let partition = self.partitions.sample(fair_coin);
+        let start = partition * self.partition_len;
+        self.clock += 1;
+
+        if !self.cache.contains_key(&partition) {
+            if self.cache.len() >= self.cache_capacity {
+                let oldest = self
+                    .cache
+                    .iter()
+                    .min_by_key(|(_, (_, used))| *used)
+                    .map(|(&p, _)| p)
+                    .expect("The cache is full, so it is not empty.");
+                self.cache.remove(&oldest);
+            }
+            let end = self.len.min(start + self.partition_len);
+            let weights: Vec<usize> = (start..end).map(&self.weight).collect();
+            let choice =
+                Choice::new(&weights).expect("A partition with mass has a non-zero weight.");
+            self.cache.insert(partition, (choice, 0));
+        }
+
+        let (choice, used) = self
+            .cache
+            .get_mut(&partition)
+            .expect("The partition was just cached.");
+        *used = self.clock;
+        start + choice.sample(fair_coin)
+    }
+}
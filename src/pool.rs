@@ -0,0 +1,144 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Bulk construction of many generators whose DDG trees share one contiguous allocation.
+//!
+//! Models such as Markov chains and hidden Markov models need one distribution per state, often
+//! tens of thousands of them. Building each row with `Generator::new` performs a separate heap
+//! allocation per row, which quickly dominates construction time. A `GeneratorPool` instead
+//! measures every row up front, allocates a single arena, and fills each row's level-label matrix
+//! in place.
+
+use alloc::{vec, vec::Vec};
+
+use crate::{sample_matrix, FairCoin, Layout};
+
+/// The location and shape of a single generator within the pool's arena.
+#[derive(Clone, Copy)]
+struct Row {
+    bucket_count: usize,
+    offset: usize,
+    len: usize,
+}
+
+/// A collection of DDG trees stored back-to-back in a single arena.
+pub struct GeneratorPool {
+    rows: Vec<Row>,
+    arena: Vec<usize>,
+}
+
+impl GeneratorPool {
+    /// Create a pool containing one DDG tree for each of the given distributions.
+    /// # Panics
+    /// Will panic if any distribution has less than two non-zero weights.
+    #[must_use]
+    pub fn new<D: AsRef<[usize]>>(distributions: &[D]) -> Self {
+        let (rows, layouts, mut arena) = Self::allocate(distributions);
+        for ((row, layout), distribution) in rows.iter().zip(&layouts).zip(distributions) {
+            layout.fill(
+                distribution.as_ref(),
+                &mut arena[row.offset..row.offset + row.len],
+            );
+        }
+        Self { rows, arena }
+    }
+
+    /// Create a pool like `GeneratorPool::new`, but fill the rows of the arena in parallel.
+    /// # Panics
+    /// Will panic if any distribution has less than two non-zero weights.
+    #[cfg(feature = "rayon")]
+    #[must_use]
+    pub fn par_new<D: AsRef<[usize]> + Sync>(distributions: &[D]) -> Self {
+        use rayon::prelude::*;
+
+        let (rows, layouts, mut arena) = Self::allocate(distributions);
+
+        // Carve the arena into disjoint mutable slices, one for each row.
+        let mut slices = Vec::with_capacity(rows.len());
+        let mut rest = arena.as_mut_slice();
+        for row in &rows {
+            let (slice, tail) = rest.split_at_mut(row.len);
+            slices.push(slice);
+            rest = tail;
+        }
+
+        slices
+            .into_par_iter()
+            .zip(layouts.par_iter())
+            .zip(distributions.par_iter())
+            .for_each(|((slice, layout), distribution)| {
+                layout.fill(distribution.as_ref(), slice);
+            });
+
+        Self { rows, arena }
+    }
+
+    /// Measure every distribution and allocate a zeroed arena large enough to hold all of them.
+    fn allocate<D: AsRef<[usize]>>(distributions: &[D]) -> (Vec<Row>, Vec<Layout>, Vec<usize>) {
+        let layouts: Vec<_> = distributions
+            .iter()
+            .map(|d| Layout::new(d.as_ref()))
+            .collect();
+
+        let mut offset = 0;
+        let rows = layouts
+            .iter()
+            .map(|layout| {
+                let len = layout.matrix_len();
+                let row = Row {
+                    bucket_count: layout.bucket_count,
+                    offset,
+                    len,
+                };
+                offset += len;
+                row
+            })
+            .collect();
+
+        (rows, layouts, vec![0; offset])
+    }
+
+    /// The number of generators in the pool.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Whether the pool contains no generators.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Sample a random item from the distribution at position `row` using a given `FairCoin`.
+    /// The item is returned as an index into that row's input distribution.
+    /// # Panics
+    /// Will panic if `row` is out of bounds.
+    pub fn sample(&self, row: usize, fair_coin: &mut impl FairCoin) -> usize {
+        let Row {
+            bucket_count,
+            offset,
+            len,
+        } = self.rows[row];
+        sample_matrix(&self.arena[offset..offset + len], bucket_count, fair_coin)
+    }
+}
@@ -0,0 +1,133 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Exact, human-readable probabilities for audit logs and documentation.
+
+use alloc::{string::ToString, vec::Vec};
+use core::fmt;
+
+use crate::{label::Label, Generator};
+
+/// The default number of decimal digits shown when no precision is given to the formatter.
+const DEFAULT_DIGITS: usize = 10;
+
+/// The exact probability of every bucket of a generator, displayed one bucket per line as a
+/// reduced fraction and a decimal expansion.
+///
+/// The decimal is computed with integer long division, so every digit shown is correct; the
+/// expansion is truncated, not rounded, and is followed by `...` if it does not terminate within
+/// the requested precision. The precision defaults to ten digits and can be set with the
+/// formatter, e.g. `format!("{:.20}", generator.probability_table())`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProbabilityTable {
+    weights: Vec<usize>,
+    sum: usize,
+}
+
+impl ProbabilityTable {
+    /// The probability of bucket `i` as a reduced fraction `(numerator, denominator)`.
+    /// # Panics
+    /// Will panic if `i` is out of bounds.
+    #[must_use]
+    pub fn fraction(&self, i: usize) -> (usize, usize) {
+        let w = self.weights[i];
+        let d = gcd(w, self.sum);
+        (w / d, self.sum / d)
+    }
+}
+
+impl Generator {
+    /// A table of the exact probability of every bucket.
+    #[must_use]
+    pub fn probability_table(&self) -> ProbabilityTable {
+        let weights = self.weights();
+        let sum = weights.iter().sum();
+        ProbabilityTable { weights, sum }
+    }
+}
+
+impl<L: Label> Generator<L> {
+    /// The probability that a single pass through the DDG tree ends on each bucket, as a reduced
+    /// dyadic rational `(numerator, exponent)` meaning `numerator / 2^exponent`, read directly
+    /// from the leaves of the tree. A bucket with a weight of zero is `(0, 0)`.
+    ///
+    /// A pass that ends on the padding label, of probability `padding_weight / 2^depth`, is
+    /// retried, so the sampled probabilities are these divided by the probability of acceptance.
+    /// They are exactly the sampled probabilities when the padding weight is zero, i.e. when the
+    /// weights sum to a power of two.
+    /// # Panics
+    /// Will panic if a weight does not fit in a `u64`, which is only possible for a generator
+    /// built from wider weights by `Generator::from_weights`.
+    #[must_use]
+    pub fn exact_probabilities(&self) -> Vec<(u64, u32)> {
+        let depth = self.depth() as u32;
+        self.wide_weights()
+            .into_iter()
+            .map(|w| {
+                if w == 0 {
+                    return (0, 0);
+                }
+                let shift = w.trailing_zeros();
+                let numerator =
+                    u64::try_from(w >> shift).expect("Every weight must fit in a `u64`.");
+                (numerator, depth - shift)
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl fmt::Display for ProbabilityTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = f.precision().unwrap_or(DEFAULT_DIGITS);
+        let index_width = self.weights.len().saturating_sub(1).to_string().len();
+        for i in 0..self.weights.len() {
+            let (numerator, denominator) = self.fraction(i);
+            write!(f, "{i:>index_width$}: {numerator}/{denominator} = ")?;
+
+            // Long division in a wider type so that `10 * remainder` cannot overflow.
+            let denominator = denominator as u128;
+            let mut remainder = numerator as u128;
+            write!(f, "{}", remainder / denominator)?;
+            remainder %= denominator;
+            if digits > 0 {
+                f.write_str(".")?;
+                for _ in 0..digits {
+                    remainder *= 10;
+                    write!(f, "{}", remainder / denominator)?;
+                    remainder %= denominator;
+                }
+            }
+            if remainder != 0 {
+                f.write_str("...")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Deterministic, location-stable sampling for procedural generation.
+//!
+//! Procedural generators want the same weighted choice every time a region of the world is
+//! generated, regardless of the order in which regions are visited. Rather than storing RNG state
+//! per region, a `RegionCoin` derives its stream of flips from the world seed, the region's
+//! coordinates, and a salt that distinguishes independent choices within the same region.
+
+use crate::{FairCoin, Generator};
+
+/// The increment of the SplitMix64 generator, derived from the golden ratio.
+pub(crate) const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// The SplitMix64 output function, a fast bijective mixer with good avalanche behavior.
+pub(crate) fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// A coin whose flips are a deterministic function of a world seed, region coordinates, and salt.
+///
+/// The stream is produced by SplitMix64. It is statistically strong and stable across platforms
+/// and versions of this crate, but it is not cryptographically secure.
+#[derive(Clone, Debug)]
+pub struct RegionCoin {
+    state: u64,
+    random_bits: u64,
+    bits_read: u32,
+}
+
+impl RegionCoin {
+    /// Create the coin for the region at `coordinates` in the world with `world_seed`. Different
+    /// `salt` values give independent streams for the same region.
+    #[must_use]
+    pub fn new(world_seed: u64, coordinates: &[i64], salt: u64) -> Self {
+        let mut state = mix(world_seed ^ GOLDEN_GAMMA);
+        for &c in coordinates {
+            // Reinterpreting the coordinate's bits keeps negative coordinates distinct.
+            state = mix(state.wrapping_add(GOLDEN_GAMMA) ^ c as u64);
+        }
+        state = mix(state.wrapping_add(GOLDEN_GAMMA) ^ salt);
+
+        Self {
+            state,
+            random_bits: 0,
+            bits_read: u64::BITS,
+        }
+    }
+}
+
+/// With the `zeroize` feature, clear the coin's position in its stream. A zeroized coin keeps
+/// flipping, but its stream no longer depends on the seed it was created from.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for RegionCoin {
+    fn zeroize(&mut self) {
+        self.state.zeroize();
+        self.random_bits.zeroize();
+        self.bits_read = u64::BITS;
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for RegionCoin {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for RegionCoin {}
+
+impl FairCoin for RegionCoin {
+    fn flip(&mut self) -> bool {
+        if self.bits_read == u64::BITS {
+            self.state = self.state.wrapping_add(GOLDEN_GAMMA);
+            self.random_bits = mix(self.state);
+            self.bits_read = 0;
+        }
+
+        let b = self.random_bits & 1 > 0;
+        self.random_bits >>= 1;
+        self.bits_read += 1;
+        b
+    }
+}
+
+impl Generator {
+    /// Sample the weighted choice for the region at `coordinates` in the world with `world_seed`.
+    /// The same arguments always produce the same index.
+    #[must_use]
+    pub fn sample_region(&self, world_seed: u64, coordinates: &[i64], salt: u64) -> usize {
+        self.sample(&mut RegionCoin::new(world_seed, coordinates, salt))
+    }
+}
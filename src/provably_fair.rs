@@ -0,0 +1,182 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Commit–reveal sampling, producing transcripts that players can verify after the fact.
+//!
+//! The operator picks a secret server seed and publishes its `commit`ment before play. Each draw
+//! combines the server seed with a client seed chosen by the player and a nonce counting draws in
+//! the session, so neither party alone controls the outcome. Once the server seed is revealed, the
+//! resulting `Transcript` can be re-run by anyone holding the same distribution.
+//!
+//! Flips are the bits, most significant first, of the blocks
+//! `SHA-256("fldr-provably-fair-v1" || len(server) || server || len(client) || client || nonce || i)`
+//! for `i = 0, 1, ...`, with integers encoded as little-endian `u64`s.
+
+use alloc::vec::Vec;
+use sha2::{Digest, Sha256};
+
+use crate::{FairCoin, Generator};
+
+/// Domain separation for the hash expansion.
+const DOMAIN: &[u8] = b"fldr-provably-fair-v1";
+
+/// The commitment to publish before any draw is made with `server_seed`.
+#[must_use]
+pub fn commit(server_seed: &[u8]) -> [u8; 32] {
+    Sha256::digest(server_seed).into()
+}
+
+/// A coin derived from a server seed, client seed, and nonce, which records every flip it makes.
+#[derive(Clone, Debug)]
+pub struct SeededCoin {
+    server_seed: Vec<u8>,
+    client_seed: Vec<u8>,
+    nonce: u64,
+    block: [u8; 32],
+    bits: Vec<bool>,
+}
+
+impl SeededCoin {
+    /// Create the coin for draw number `nonce` of a session.
+    #[must_use]
+    pub fn new(server_seed: &[u8], client_seed: &[u8], nonce: u64) -> Self {
+        Self {
+            server_seed: server_seed.to_vec(),
+            client_seed: client_seed.to_vec(),
+            nonce,
+            block: [0; 32],
+            bits: Vec::new(),
+        }
+    }
+
+    /// The flips made so far, in order.
+    #[must_use]
+    pub fn bits(&self) -> &[bool] {
+        &self.bits
+    }
+
+    /// Compute block `index` of the expanded stream.
+    fn expand(&self, index: u64) -> [u8; 32] {
+        Sha256::new()
+            .chain_update(DOMAIN)
+            .chain_update((self.server_seed.len() as u64).to_le_bytes())
+            .chain_update(&self.server_seed)
+            .chain_update((self.client_seed.len() as u64).to_le_bytes())
+            .chain_update(&self.client_seed)
+            .chain_update(self.nonce.to_le_bytes())
+            .chain_update(index.to_le_bytes())
+            .finalize()
+            .into()
+    }
+}
+
+/// With the `zeroize` feature, clear both seeds, the recorded flips, and the current block of the
+/// expanded stream, so an unrevealed server seed does not linger in memory.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for SeededCoin {
+    fn zeroize(&mut self) {
+        self.server_seed.zeroize();
+        self.client_seed.zeroize();
+        self.block.zeroize();
+        self.bits.iter_mut().for_each(zeroize::Zeroize::zeroize);
+        self.bits.clear();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for SeededCoin {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for SeededCoin {}
+
+impl FairCoin for SeededCoin {
+    fn flip(&mut self) -> bool {
+        let bit = self.bits.len() % 256;
+        if bit == 0 {
+            self.block = self.expand((self.bits.len() / 256) as u64);
+        }
+        let value = self.block[bit / 8] >> (7 - bit % 8) & 1 > 0;
+        self.bits.push(value);
+        value
+    }
+}
+
+/// Everything needed to confirm a single draw once the server seed has been revealed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transcript {
+    /// The commitment published before the draw.
+    pub commitment: [u8; 32],
+
+    /// The revealed server seed.
+    pub server_seed: Vec<u8>,
+
+    /// The seed chosen by the player.
+    pub client_seed: Vec<u8>,
+
+    /// The position of this draw within the session.
+    pub nonce: u64,
+
+    /// The flips consumed by the draw, in order.
+    pub bits: Vec<bool>,
+
+    /// The sampled index.
+    pub index: usize,
+}
+
+impl Transcript {
+    /// Check that the server seed matches the commitment, and that re-running the draw against
+    /// `generator` consumes exactly the recorded bits and lands on the recorded index.
+    #[must_use]
+    pub fn verify(&self, generator: &Generator) -> bool {
+        if commit(&self.server_seed) != self.commitment {
+            return false;
+        }
+        let replay = generator.draw_provably_fair(&self.server_seed, &self.client_seed, self.nonce);
+        replay.bits == self.bits && replay.index == self.index
+    }
+}
+
+impl Generator {
+    /// Draw a single index for draw number `nonce` of a session, returning its transcript.
+    #[must_use]
+    pub fn draw_provably_fair(
+        &self,
+        server_seed: &[u8],
+        client_seed: &[u8],
+        nonce: u64,
+    ) -> Transcript {
+        let mut coin = SeededCoin::new(server_seed, client_seed, nonce);
+        let index = self.sample(&mut coin);
+        Transcript {
+            commitment: commit(server_seed),
+            server_seed: core::mem::take(&mut coin.server_seed),
+            client_seed: core::mem::take(&mut coin.client_seed),
+            nonce,
+            bits: core::mem::take(&mut coin.bits),
+            index,
+        }
+    }
+}
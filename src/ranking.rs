@@ -0,0 +1,125 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Sampling rankings under the Plackett–Luce model.
+
+use alloc::vec::Vec;
+
+use crate::{fenwick::Fenwick, uniform::Uniform, FairCoin, Generator};
+
+/// Samples rankings under the Plackett–Luce model, in which the first place is chosen with
+/// probability proportional to the weights, and each later place is chosen the same way from the
+/// items that remain.
+///
+/// Each ranking removes items from a copy of a Fenwick tree of the weights, so a ranking of the
+/// top `m` of `n` items takes `O(n + m log n)` time. Items with a weight of zero are never ranked.
+#[derive(Clone, Debug)]
+pub struct PlackettLuce {
+    weights: Vec<usize>,
+    tree: Fenwick,
+    rankable: usize,
+}
+
+impl PlackettLuce {
+    /// Create a sampler of rankings of the items `0..weights.len()`.
+    /// # Panics
+    /// Will panic if the sum of the weights overflows a `usize`.
+    #[must_use]
+    pub fn new(weights: &[usize]) -> Self {
+        weights
+            .iter()
+            .try_fold(0usize, |sum, &w| sum.checked_add(w))
+            .expect("The sum of the weights must fit in a `usize`.");
+        Self {
+            weights: weights.to_vec(),
+            tree: Fenwick::new(weights),
+            rankable: weights.iter().filter(|&&w| w > 0).count(),
+        }
+    }
+
+    /// The weight of every item.
+    #[must_use]
+    pub fn weights(&self) -> &[usize] {
+        &self.weights
+    }
+
+    /// The number of items with a non-zero weight, which is the length of a full ranking.
+    #[must_use]
+    pub fn rankable(&self) -> usize {
+        self.rankable
+    }
+
+    /// Sample a ranking of every item with a non-zero weight, best first, using a given
+    /// `FairCoin`.
+    pub fn sample_ranking(&self, fair_coin: &mut impl FairCoin) -> Vec<usize> {
+        self.sample_top(fair_coin, self.rankable)
+    }
+
+    /// Sample the first `m` places of a ranking, best first, using a given `FairCoin`.
+    /// # Panics
+    /// Will panic if `m` is greater than the number of items with a non-zero weight.
+    pub fn sample_top(&self, fair_coin: &mut impl FairCoin, m: usize) -> Vec<usize> {
+        assert!(
+            m <= self.rankable,
+            "Only items with a non-zero weight can be ranked."
+        );
+        let mut remaining = self.tree.clone();
+        (0..m)
+            .map(|_| {
+                let i = remaining.search(Uniform::new(remaining.total()).sample(fair_coin));
+                remaining.sub(i, self.weights[i]);
+                i
+            })
+            .collect()
+    }
+}
+
+impl Generator {
+    /// Sample `k` distinct indices, best first, where each is chosen with probability proportional
+    /// to its weight among the indices not chosen yet, i.e. successive sampling without
+    /// replacement. The draws are made from a temporary Fenwick tree of the weights, from which
+    /// each chosen index is removed, so this takes `O(n + k log n)` time rather than rebuilding the
+    /// generator after every draw.
+    /// # Panics
+    /// Will panic if `k` is greater than the number of buckets with a non-zero weight.
+    pub fn sample_distinct(&self, fair_coin: &mut impl FairCoin, k: usize) -> Vec<usize> {
+        PlackettLuce::new(&self.weights()).sample_top(fair_coin, k)
+    }
+
+    /// Sample a permutation of every bucket in which heavier buckets tend to come first, e.g. for
+    /// ranked recommendations or playlist shuffling. The buckets with a non-zero weight are ordered
+    /// as by `Generator::sample_distinct`, and are followed by the buckets with a weight of zero in
+    /// a uniformly random order.
+    pub fn weighted_shuffle(&self, fair_coin: &mut impl FairCoin) -> Vec<usize> {
+        let weights = self.weights();
+        let mut permutation = PlackettLuce::new(&weights).sample_ranking(fair_coin);
+        let ranked = permutation.len();
+        permutation.extend((0..weights.len()).filter(|&i| weights[i] == 0));
+
+        // Shuffle the buckets with a weight of zero with the Fisher–Yates algorithm.
+        for i in (ranked + 1..permutation.len()).rev() {
+            let j = ranked + Uniform::new(i - ranked + 1).sample(fair_coin);
+            permutation.swap(i, j);
+        }
+        permutation
+    }
+}
@@ -0,0 +1,145 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Exchanging DDG trees with the reference C implementation of FLDR, so that trees preprocessed by
+//! either side can be cross-validated against the other.
+//!
+//! The reference implementation preprocesses a distribution of `n` integer weights summing to `m`
+//! into a `fldr_preprocess_t` holding the depth `k`, the padding weight `r = 2^k - m`, the leaf
+//! count `h[j]` of each level `j`, and a flat `(n + 1) * k` matrix `H` whose entry `H[d * k + j]`
+//! is the label of leaf `d` of level `j`, with `n` as the padding label. The text format is those
+//! fields in declaration order as whitespace-separated decimal integers:
+//!
+//! ```text
+//! n m k r
+//! h[0] ... h[k - 1]
+//! H[0] ... H[k - 1]
+//! ...
+//! H[n * k] ... H[n * k + k - 1]
+//! ```
+//!
+//! Entries of `H` past the leaf count of their level are unused; they are written as zero, and
+//! ignored when read.
+
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt::{Display, Write};
+
+use crate::{label::Label, levels, Error, Generator};
+
+impl<L: Label> Generator<L> {
+    /// Write the DDG tree in the text format of the reference implementation, described in the
+    /// [module documentation](self).
+    #[must_use]
+    pub fn to_reference_text(&self) -> String {
+        let n = self.bucket_count;
+        let k = self.depth();
+        let sum: u128 = self.wide_weights().iter().sum();
+
+        // Lay the leaves out column by column in the reference implementation's flat matrix.
+        let mut matrix = vec![0; (n + 1) * k];
+        for (j, labels) in levels(&self.level_label_matrix).enumerate() {
+            for (d, label) in labels.iter().enumerate() {
+                matrix[d * k + j] = label.to_usize();
+            }
+        }
+
+        let mut text = String::new();
+        write_line(
+            &mut text,
+            [n as u128, sum, k as u128, self.padding_weight()],
+        );
+        write_line(&mut text, self.leaf_counts());
+        for row in matrix.chunks(k) {
+            write_line(&mut text, row);
+        }
+        text
+    }
+}
+
+impl Generator {
+    /// Read a DDG tree in the text format of the reference implementation, described in the
+    /// [module documentation](self).
+    /// # Errors
+    /// Returns `Error::InvalidEncoding` if the text is not the expected number of non-negative
+    /// integers for its header, or if a value does not fit in a `usize` on this target. Returns
+    /// `Error::MalformedTree` if the tree could leave its bounds or never terminate, or if its
+    /// weights do not sum to `m` with a padding weight of `r`.
+    pub fn from_reference_text(text: &str) -> Result<Self, Error> {
+        let mut values = text
+            .split_ascii_whitespace()
+            .map(|value| value.parse::<u128>().map_err(|_| Error::InvalidEncoding));
+        let mut next = || values.next().unwrap_or(Err(Error::InvalidEncoding));
+        let to_usize = |value: u128| usize::try_from(value).map_err(|_| Error::InvalidEncoding);
+
+        let n = to_usize(next()?)?;
+        let m = next()?;
+        let k = to_usize(next()?)?;
+        let r = next()?;
+        if k > u128::BITS as usize {
+            // The weights and the padding weight could not sum to `2^k` in a `u128`.
+            return Err(Error::MalformedTree);
+        }
+        let h = (0..k)
+            .map(|_| to_usize(next()?))
+            .collect::<Result<Vec<_>, _>>()?;
+        let len = n
+            .checked_add(1)
+            .and_then(|rows| rows.checked_mul(k))
+            .ok_or(Error::InvalidEncoding)?;
+        let matrix = (0..len)
+            .map(|_| to_usize(next()?))
+            .collect::<Result<Vec<_>, _>>()?;
+        if next().is_ok() {
+            return Err(Error::InvalidEncoding);
+        }
+
+        // Gather the leaves of each level, which the reference implementation stores in a column.
+        let mut level_label_matrix = Vec::new();
+        for (j, &count) in h.iter().enumerate() {
+            if count > n + 1 {
+                return Err(Error::MalformedTree);
+            }
+            level_label_matrix.push(count);
+            level_label_matrix.extend((0..count).map(|d| matrix[d * k + j]));
+        }
+
+        let generator = Self::try_from_matrix(n, level_label_matrix.into())?;
+        let sum = generator
+            .wide_weights()
+            .into_iter()
+            .try_fold(0u128, u128::checked_add);
+        if sum != Some(m) || generator.padding_weight() != r {
+            return Err(Error::MalformedTree);
+        }
+        Ok(generator)
+    }
+}
+
+/// Write `values` to `text` as one line of space-separated decimals.
+fn write_line<T: Display>(text: &mut String, values: impl IntoIterator<Item = T>) {
+    let mut separator = "";
+    for value in values {
+        write!(text, "{separator}{value}").expect("Writing to a `String` cannot fail.");
+        separator = " ";
+    }
+    text.push('\n');
+}
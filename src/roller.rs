@@ -0,0 +1,84 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! A `Generator` bundled together with the coin used to sample it.
+
+use crate::{FairCoin, Generator};
+
+/// Pairs a `Generator` with its own `FairCoin`, for callers that always sample a distribution with
+/// the same source of randomness.
+///
+/// With the `serde` feature, a roller is serializable whenever its coin is, which allows long
+/// simulations to checkpoint and later resume the exact same stream of samples. The checkpoint
+/// stores the generator in the same format as a serialized `Generator`, so the DDG tree is
+/// validated but never rebuilt when it is loaded.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Roller<C: FairCoin> {
+    generator: Generator,
+    coin: C,
+}
+
+impl<C: FairCoin> Roller<C> {
+    /// Bundle `generator` with the coin that will be used to sample it.
+    #[must_use]
+    pub fn new(generator: Generator, coin: C) -> Self {
+        Self { generator, coin }
+    }
+
+    /// Sample a random item from the generator using the bundled coin.
+    pub fn sample(&mut self) -> usize {
+        self.generator.sample(&mut self.coin)
+    }
+
+    /// The generator being sampled.
+    #[must_use]
+    pub fn generator(&self) -> &Generator {
+        &self.generator
+    }
+
+    /// The bundled coin.
+    pub fn coin_mut(&mut self) -> &mut C {
+        &mut self.coin
+    }
+
+    /// Consume the roller and return its generator and coin.
+    pub fn into_parts(self) -> (Generator, C) {
+        (self.generator, self.coin)
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl<R: rand_core::RngCore + rand_core::SeedableRng> Roller<crate::rand_core::RngCoin<R>> {
+    /// Create a child roller over the same generator with a coin seeded from this roller's coin.
+    /// Forks are deterministic, so a seeded parent gives each parallel task an independent,
+    /// reproducible stream of samples. The generator is shared, not copied.
+    /// # Panics
+    /// Will panic if the parent RNG fails to produce a seed.
+    #[must_use]
+    pub fn fork(&mut self) -> Self {
+        Self {
+            generator: self.generator.clone(),
+            coin: self.coin.fork(),
+        }
+    }
+}
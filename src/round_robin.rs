@@ -0,0 +1,83 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Deterministic proportional scheduling over the same weights as a `Generator`.
+
+use alloc::{vec, vec::Vec};
+
+use crate::Generator;
+
+/// An infinite iterator over indices produced by smooth weighted round-robin, as popularized by
+/// nginx. Over every `sum(weights)` consecutive items, each index appears exactly as often as its
+/// weight, and appearances of each index are spread as evenly as possible.
+#[derive(Clone, Debug)]
+pub struct SmoothWeightedRoundRobin {
+    weights: Vec<usize>,
+    current: Vec<i128>,
+    total: i128,
+}
+
+impl SmoothWeightedRoundRobin {
+    /// Create a scheduler over `weights`.
+    /// # Panics
+    /// Will panic if every weight is zero.
+    #[must_use]
+    pub fn new(weights: &[usize]) -> Self {
+        // Every weight fits in an `i128`, and so does their sum for any realistic length.
+        let total = weights.iter().map(|&w| w as i128).sum();
+        assert!(total > 0, "At least one weight must be non-zero.");
+        Self {
+            weights: weights.to_vec(),
+            current: vec![0; weights.len()],
+            total,
+        }
+    }
+}
+
+impl Iterator for SmoothWeightedRoundRobin {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        // Raise every index by its weight, choose the highest (the first on ties), and lower the
+        // chosen index by the total weight.
+        for (c, &w) in self.current.iter_mut().zip(&self.weights) {
+            *c += w as i128;
+        }
+        let best = self
+            .current
+            .iter()
+            .enumerate()
+            .max_by(|(i, a), (j, b)| a.cmp(b).then(j.cmp(i)))
+            .map(|(i, _)| i)?;
+        self.current[best] -= self.total;
+        Some(best)
+    }
+}
+
+impl Generator {
+    /// A deterministic smooth weighted round-robin over the weights of this generator, so that
+    /// randomized and deterministic proportional scheduling can share one weight definition.
+    #[must_use]
+    pub fn smooth_wrr(&self) -> SmoothWeightedRoundRobin {
+        SmoothWeightedRoundRobin::new(&self.weights())
+    }
+}
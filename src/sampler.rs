@@ -0,0 +1,93 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! A common interface over the crate's samplers, for code that should work with any of them.
+
+use crate::{
+    codegen::StaticGenerator,
+    dynamic::DynamicSampler,
+    uniform::{HeadTailGenerator, Uniform},
+    FairCoin, Generator,
+};
+
+/// Samples an index in `0..bucket_count()` from a fixed or changing discrete distribution.
+///
+/// `Generator` is the best choice for distributions that rarely change, since it samples with
+/// near-optimal entropy but must be rebuilt on every change. `DynamicSampler` updates single
+/// weights in logarithmic time and is the better choice when weights change constantly.
+pub trait DiscreteSampler {
+    /// The number of buckets, including any with zero weight.
+    fn bucket_count(&self) -> usize;
+
+    /// Sample a random index in the range `0..bucket_count()` using a given `FairCoin`.
+    fn sample<C: FairCoin>(&self, fair_coin: &mut C) -> usize;
+}
+
+impl DiscreteSampler for Generator {
+    fn bucket_count(&self) -> usize {
+        self.bucket_count
+    }
+
+    fn sample<C: FairCoin>(&self, fair_coin: &mut C) -> usize {
+        Generator::sample(self, fair_coin)
+    }
+}
+
+impl DiscreteSampler for StaticGenerator<'_> {
+    fn bucket_count(&self) -> usize {
+        StaticGenerator::bucket_count(self)
+    }
+
+    fn sample<C: FairCoin>(&self, fair_coin: &mut C) -> usize {
+        StaticGenerator::sample(self, fair_coin)
+    }
+}
+
+impl DiscreteSampler for Uniform {
+    fn bucket_count(&self) -> usize {
+        self.len()
+    }
+
+    fn sample<C: FairCoin>(&self, fair_coin: &mut C) -> usize {
+        Uniform::sample(self, fair_coin)
+    }
+}
+
+impl DiscreteSampler for HeadTailGenerator {
+    fn bucket_count(&self) -> usize {
+        self.len()
+    }
+
+    fn sample<C: FairCoin>(&self, fair_coin: &mut C) -> usize {
+        HeadTailGenerator::sample(self, fair_coin)
+    }
+}
+
+impl DiscreteSampler for DynamicSampler {
+    fn bucket_count(&self) -> usize {
+        self.len()
+    }
+
+    fn sample<C: FairCoin>(&self, fair_coin: &mut C) -> usize {
+        DynamicSampler::sample(self, fair_coin)
+    }
+}
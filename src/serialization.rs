@@ -0,0 +1,66 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Serialization of a `Generator` as its preprocessed DDG tree, so that large trees can be cached
+//! or sent between processes instead of being rebuilt.
+//!
+//! The packed level-label matrix is serialized as is. Deserializing checks that the matrix is a
+//! complete DDG tree whose labels are in bounds, which takes time linear in its size, but never
+//! rebuilds it.
+
+use alloc::sync::Arc;
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Generator;
+
+#[derive(Serialize)]
+struct GeneratorRef<'a> {
+    bucket_count: usize,
+    level_label_matrix: &'a [usize],
+}
+
+#[derive(Deserialize)]
+struct GeneratorState {
+    bucket_count: usize,
+    level_label_matrix: Arc<[usize]>,
+}
+
+impl Serialize for Generator {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GeneratorRef {
+            bucket_count: self.bucket_count,
+            level_label_matrix: &self.level_label_matrix,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Generator {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let GeneratorState {
+            bucket_count,
+            level_label_matrix,
+        } = GeneratorState::deserialize(deserializer)?;
+        Self::try_from_matrix(bucket_count, level_label_matrix).map_err(D::Error::custom)
+    }
+}
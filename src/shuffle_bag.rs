@@ -0,0 +1,81 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Weighted sampling without replacement from a bag that refills itself when empty.
+
+use alloc::vec::Vec;
+
+use crate::{fenwick::Fenwick, uniform::Uniform, FairCoin};
+
+/// A bag holding `weights[i]` copies of each index `i`. Each sample removes one random copy from
+/// the bag, and the bag is refilled once it is empty, so over every full cycle each index is
+/// sampled exactly as many times as its weight. Game designers often prefer this to independent
+/// sampling, which allows long droughts and streaks.
+#[derive(Clone, Debug)]
+pub struct ShuffleBag {
+    weights: Vec<usize>,
+    remaining: Fenwick,
+}
+
+impl ShuffleBag {
+    /// Create a full bag with `weights[i]` copies of each index `i`.
+    /// # Panics
+    /// Will panic if every weight is zero, or if the sum of the weights overflows a `usize`.
+    #[must_use]
+    pub fn new(weights: &[usize]) -> Self {
+        let sum = weights
+            .iter()
+            .try_fold(0usize, |sum, &w| sum.checked_add(w))
+            .expect("The sum of the weights must fit in a `usize`.");
+        assert!(sum > 0, "The bag must contain at least one item.");
+        Self {
+            weights: weights.to_vec(),
+            remaining: Fenwick::new(weights),
+        }
+    }
+
+    /// The number of items left in the bag before it is refilled.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.remaining.total()
+    }
+
+    /// Refill the bag to its initial contents.
+    pub fn refill(&mut self) {
+        self.remaining = Fenwick::new(&self.weights);
+    }
+
+    /// Remove a uniformly random item from the bag, refilling it first if it is empty.
+    pub fn sample(&mut self, fair_coin: &mut impl FairCoin) -> usize {
+        let mut remaining = self.remaining.total();
+        if remaining == 0 {
+            self.refill();
+            remaining = self.remaining.total();
+        }
+
+        let i = self
+            .remaining
+            .search(Uniform::new(remaining).sample(fair_coin));
+        self.remaining.sub(i, 1);
+        i
+    }
+}
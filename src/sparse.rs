@@ -0,0 +1,74 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Sampling from distributions over huge domains in which most outcomes have zero weight.
+
+use alloc::vec::Vec;
+
+use crate::{FairCoin, Generator};
+
+/// A generator over sparse `(index, weight)` pairs. Unlisted indices have zero weight, and memory
+/// scales with the number of non-zero weights rather than the size of the domain.
+#[derive(Clone)]
+pub struct SparseGenerator {
+    indices: Vec<u64>,
+    generator: Generator,
+}
+
+impl SparseGenerator {
+    /// Create a generator from `(index, weight)` pairs. Pairs with the same index have their
+    /// weights added together, and pairs with zero weight are ignored.
+    /// # Panics
+    /// Will panic if less than two indices have a non-zero weight, or if the sum of the weights
+    /// overflows a `usize`.
+    #[must_use]
+    pub fn new(entries: &[(u64, usize)]) -> Self {
+        let mut entries: Vec<_> = entries.iter().copied().filter(|&(_, w)| w > 0).collect();
+        entries.sort_unstable_by_key(|&(i, _)| i);
+        entries.dedup_by(|(i, w), (j, total)| {
+            let merge = i == j;
+            if merge {
+                *total = total
+                    .checked_add(*w)
+                    .expect("The sum of the weights must fit in a `usize`.");
+            }
+            merge
+        });
+
+        let (indices, weights): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+        Self {
+            indices,
+            generator: Generator::new(&weights),
+        }
+    }
+
+    /// The indices with a non-zero weight, in increasing order.
+    #[must_use]
+    pub fn indices(&self) -> &[u64] {
+        &self.indices
+    }
+
+    /// Sample a random index from the sparse distribution using a given `FairCoin`.
+    pub fn sample(&self, fair_coin: &mut impl FairCoin) -> u64 {
+        self.indices[self.generator.sample(fair_coin)]
+    }
+}
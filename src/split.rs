@@ -0,0 +1,141 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Decomposition of a generator into two halves and an exact gate choosing between them.
+
+use alloc::vec::Vec;
+use core::cell::OnceCell;
+
+use crate::{choice::Choice, FairCoin, Generator};
+
+/// The buckets on one side of a split, whose DDG tree is built from their weights the first time
+/// the half is sampled.
+#[derive(Clone)]
+struct Half {
+    weights: Vec<usize>,
+    choice: OnceCell<Choice>,
+}
+
+impl Half {
+    fn new(weights: &[usize]) -> Self {
+        Self {
+            weights: weights.to_vec(),
+            choice: OnceCell::new(),
+        }
+    }
+
+    /// Sample the half, building it first if needed. The gate only selects a half with a
+    /// non-zero weight, so a half that is sampled always has a possible outcome.
+    fn sample(&self, fair_coin: &mut impl FairCoin) -> usize {
+        self.choice
+            .get_or_init(|| {
+                Choice::new(&self.weights).expect("The gate only selects halves with weight.")
+            })
+            .sample(fair_coin)
+    }
+}
+
+/// A distribution split into the buckets before and after an index. Sampling the gate and then
+/// the chosen half reproduces the original distribution exactly.
+///
+/// Each half keeps its weights and is built only when the gate first selects it, so a half that is
+/// rarely hit costs nothing until it is needed. Either half may have a single non-zero weight, or
+/// none at all, in which case the gate never selects it.
+#[derive(Clone)]
+pub struct Split {
+    gate: Choice,
+    left: Half,
+    right: Half,
+    mid: usize,
+}
+
+impl Split {
+    /// The index at which the original distribution was split.
+    #[must_use]
+    pub fn mid(&self) -> usize {
+        self.mid
+    }
+
+    /// The total weights of the buckets before and from the split index, in which proportion the
+    /// gate selects each half.
+    #[must_use]
+    pub fn gate_weights(&self) -> [usize; 2] {
+        [
+            self.left.weights.iter().sum(),
+            self.right.weights.iter().sum(),
+        ]
+    }
+
+    /// The weights of the buckets before the split index.
+    #[must_use]
+    pub fn left_weights(&self) -> &[usize] {
+        &self.left.weights
+    }
+
+    /// The weights of the buckets from the split index onward, re-indexed from zero.
+    #[must_use]
+    pub fn right_weights(&self) -> &[usize] {
+        &self.right.weights
+    }
+
+    /// Whether the buckets before the split index have been built, i.e. sampled at least once.
+    #[must_use]
+    pub fn is_left_built(&self) -> bool {
+        self.left.choice.get().is_some()
+    }
+
+    /// Whether the buckets from the split index onward have been built, i.e. sampled at least once.
+    #[must_use]
+    pub fn is_right_built(&self) -> bool {
+        self.right.choice.get().is_some()
+    }
+
+    /// Sample the gate and then the chosen half, returning an index into the original distribution.
+    pub fn sample(&self, fair_coin: &mut impl FairCoin) -> usize {
+        if self.gate.sample(fair_coin) == 0 {
+            self.left.sample(fair_coin)
+        } else {
+            self.mid + self.right.sample(fair_coin)
+        }
+    }
+}
+
+impl Generator {
+    /// Decompose this generator into the buckets `[0, mid)`, the buckets `[mid, len)`, and an
+    /// exact Bernoulli gate between them. This allows huge distributions to be refactored
+    /// hierarchically, building a rarely-selected half only when it is first needed.
+    /// # Panics
+    /// Will panic if `mid` is greater than the number of buckets.
+    #[must_use]
+    pub fn split_at(&self, mid: usize) -> Split {
+        let weights = self.weights();
+        let (left, right) = weights.split_at(mid);
+        let gate = Choice::new(&[left.iter().sum(), right.iter().sum()])
+            .expect("A generator has at least two non-zero weights.");
+        Split {
+            gate,
+            left: Half::new(left),
+            right: Half::new(right),
+            mid,
+        }
+    }
+}
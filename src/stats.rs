@@ -0,0 +1,74 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The shape of a DDG tree, for budgeting the entropy that a generator consumes.
+
+use alloc::vec::Vec;
+
+use crate::{label::Label, levels, Generator};
+
+impl<L: Label> Generator<L> {
+    /// The number of leaves on each level of the DDG tree, from the root down, including the
+    /// leaves of the padding label.
+    #[must_use]
+    pub fn leaf_counts(&self) -> Vec<usize> {
+        levels(&self.level_label_matrix).map(<[L]>::len).collect()
+    }
+
+    /// The weight of the padding label, i.e. the difference between the sum of the weights and the
+    /// next power of two. A sample that reaches one of its leaves is rejected and restarts from the
+    /// root, which happens with probability `padding_weight / 2^depth` per attempt.
+    #[must_use]
+    pub fn padding_weight(&self) -> u128 {
+        let depth = self.depth();
+        levels(&self.level_label_matrix)
+            .enumerate()
+            .filter(|(_, labels)| {
+                labels
+                    .last()
+                    .is_some_and(|label| label.to_usize() >= self.bucket_count)
+            })
+            .map(|(j, _)| 1u128 << (depth - j - 1))
+            .sum()
+    }
+
+    /// The exact expected number of coin flips consumed per sample, up to the rounding of the
+    /// final `f64`.
+    ///
+    /// A single pass from the root ends on level `j` (counting from one) with probability
+    /// `leaf_counts[j - 1] / 2^j`, and only passes ending outside of the padding label are
+    /// accepted, so by Wald's identity the expected flips per sample are the expected flips per
+    /// pass divided by the probability that a pass is accepted.
+    #[must_use]
+    pub fn expected_flips(&self) -> f64 {
+        // `scale` is the probability of reaching any one node of the current level, halved on every
+        // level since `no_std` has no `f64::powi`.
+        let mut scale = 1.;
+        let mut flips_per_pass = 0.;
+        for (j, count) in self.leaf_counts().into_iter().enumerate() {
+            scale /= 2.;
+            flips_per_pass += (j + 1) as f64 * count as f64 * scale;
+        }
+        let rejection = self.padding_weight() as f64 * scale;
+        flips_per_pass / (1. - rejection)
+    }
+}
@@ -0,0 +1,181 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Weighted test-data generation for property-based testing frameworks.
+//!
+//! With the `proptest` feature, `WeightedValues` is a proptest `Strategy` that picks one of a set
+//! of values with exactly the given weights, and shrinks towards the values listed first. With
+//! the `quickcheck` feature, `WeightedValues::pick_quickcheck` picks a value using a quickcheck
+//! `Gen`. Listing the simplest values first, e.g. `(90, "ascii"), (9, "latin-1"), (1, "emoji")`,
+//! makes failures shrink to the simplest value that still fails.
+
+use std::sync::Arc;
+
+use crate::{choice::Choice, FairCoin};
+
+/// A set of values, each picked with probability proportional to its weight.
+#[derive(Clone)]
+pub struct WeightedValues<T> {
+    values: Arc<[T]>,
+    choice: Choice,
+}
+
+impl<T> WeightedValues<T> {
+    /// Create a set of `(weight, value)` pairs.
+    /// # Panics
+    /// Will panic if every weight is zero, or if the sum of the weights overflows a `usize`.
+    #[must_use]
+    pub fn new(choices: impl IntoIterator<Item = (usize, T)>) -> Self {
+        let (weights, values): (Vec<_>, Vec<_>) = choices.into_iter().unzip();
+        Self {
+            values: values.into(),
+            choice: Choice::new(&weights).expect("At least one weight must be non-zero."),
+        }
+    }
+
+    /// Pick a value using a given `FairCoin`.
+    pub fn pick(&self, fair_coin: &mut impl FairCoin) -> &T {
+        &self.values[self.choice.sample(fair_coin)]
+    }
+
+    /// Pick a value using a quickcheck `Gen`. Quickcheck shrinks by type rather than by strategy,
+    /// so values picked this way shrink however their type's `Arbitrary` implementation does.
+    #[cfg(feature = "quickcheck")]
+    pub fn pick_quickcheck(&self, g: &mut quickcheck::Gen) -> &T {
+        self.pick(&mut GenCoin(g))
+    }
+}
+
+/// A coin that flips with quickcheck's `Gen::choose`, which is uniform over its slice.
+#[cfg(feature = "quickcheck")]
+struct GenCoin<'a>(&'a mut quickcheck::Gen);
+
+#[cfg(feature = "quickcheck")]
+impl FairCoin for GenCoin<'_> {
+    fn flip(&mut self) -> bool {
+        *self
+            .0
+            .choose(&[false, true])
+            .expect("The slice is not empty.")
+    }
+}
+
+#[cfg(feature = "proptest")]
+mod strategy {
+    use std::{fmt, sync::Arc};
+
+    use proptest::{
+        prelude::Rng,
+        strategy::{NewTree, Strategy, ValueTree},
+        test_runner::{TestRng, TestRunner},
+    };
+
+    use super::WeightedValues;
+    use crate::FairCoin;
+
+    /// A coin that takes bits from the test runner's RNG, so that runs are reproducible from the
+    /// runner's seed.
+    struct RunnerCoin<'a> {
+        rng: &'a mut TestRng,
+        random_bits: u64,
+        bits_read: u32,
+    }
+
+    impl FairCoin for RunnerCoin<'_> {
+        fn flip(&mut self) -> bool {
+            if self.bits_read == u64::BITS {
+                self.random_bits = self.rng.next_u64();
+                self.bits_read = 0;
+            }
+            let b = self.random_bits & 1 > 0;
+            self.random_bits >>= 1;
+            self.bits_read += 1;
+            b
+        }
+    }
+
+    impl<T: Clone + fmt::Debug> fmt::Debug for WeightedValues<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("WeightedValues")
+                .field("values", &self.values)
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl<T: Clone + fmt::Debug> Strategy for WeightedValues<T> {
+        type Tree = WeightedValueTree<T>;
+        type Value = T;
+
+        fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+            let mut coin = RunnerCoin {
+                rng: runner.rng(),
+                random_bits: 0,
+                bits_read: u64::BITS,
+            };
+            let index = self.choice.sample(&mut coin);
+            Ok(WeightedValueTree {
+                values: Arc::clone(&self.values),
+                low: 0,
+                current: index,
+                high: index,
+            })
+        }
+    }
+
+    /// The value tree of `WeightedValues`, which shrinks by binary search towards the first value.
+    #[derive(Clone, Debug)]
+    pub struct WeightedValueTree<T> {
+        values: Arc<[T]>,
+        low: usize,
+        current: usize,
+        high: usize,
+    }
+
+    impl<T: Clone + fmt::Debug> ValueTree for WeightedValueTree<T> {
+        type Value = T;
+
+        fn current(&self) -> T {
+            self.values[self.current].clone()
+        }
+
+        fn simplify(&mut self) -> bool {
+            if self.current <= self.low {
+                return false;
+            }
+            self.high = self.current;
+            self.current = self.low + (self.high - self.low) / 2;
+            true
+        }
+
+        fn complicate(&mut self) -> bool {
+            if self.current >= self.high {
+                return false;
+            }
+            self.low = self.current + 1;
+            self.current = self.low + (self.high - self.low) / 2;
+            true
+        }
+    }
+}
+
+#[cfg(feature = "proptest")]
+pub use strategy::WeightedValueTree;
@@ -0,0 +1,200 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Uniform sampling with the Fast Dice Roller, and distributions with a large uniform component.
+//!
+//! The [Fast Dice Roller (FDR)](https://arxiv.org/pdf/1304.1916.pdf) by Lumbroso is the uniform
+//! special case that FLDR generalizes. It samples one of `n` equally likely outcomes in constant
+//! space, so a block of equally weighted buckets never needs to be stored as DDG tree leaves.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{FairCoin, Generator};
+
+/// Samples one of `n` equally likely outcomes with the Fast Dice Roller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Uniform {
+    n: usize,
+}
+
+impl Uniform {
+    /// Create a uniform sampler over `0..n`.
+    /// # Panics
+    /// Will panic if `n` is zero.
+    #[must_use]
+    pub fn new(n: usize) -> Self {
+        assert!(
+            n > 0,
+            "The uniform distribution must have at least one outcome."
+        );
+        Self { n }
+    }
+
+    /// The number of equally likely outcomes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    /// Whether there are no outcomes. Always `false`, since `n` must be positive.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Sample a uniformly random integer in `0..n` using a given `FairCoin`.
+    pub fn sample(&self, fair_coin: &mut impl FairCoin) -> usize {
+        // `v` tracks the range of the random integer `c`. Doubling `v` may exceed a `usize` when
+        // `n` is close to `usize::MAX`, so both are tracked in a wider type.
+        let n = self.n as u128;
+        let mut v: u128 = 1;
+        let mut c: u128 = 0;
+        loop {
+            v <<= 1;
+            c = (c << 1) + u128::from(fair_coin.flip());
+            if v >= n {
+                if c < n {
+                    // `c < n` and `n` is a `usize`, so the conversion is lossless.
+                    return c as usize;
+                }
+
+                // Keep the leftover entropy in `c - n`, which is uniform over `0..v - n`.
+                v -= n;
+                c -= n;
+            }
+        }
+    }
+}
+
+/// A distribution of explicitly weighted buckets followed by a tail of equally weighted buckets.
+/// The tail is sampled with the Fast Dice Roller, so it costs constant space however long it is.
+#[derive(Clone)]
+pub struct HeadTailGenerator {
+    gate: Option<Generator>,
+    head_len: usize,
+    tail: Option<Uniform>,
+}
+
+impl HeadTailGenerator {
+    /// Create a generator over the buckets of `head`, followed by `tail_len` buckets that each have
+    /// weight `tail_weight`. Sampled indices `0..head.len()` refer to the head and larger indices
+    /// refer to the tail.
+    /// # Panics
+    /// Will panic if less than two buckets have a non-zero weight, or if the total weight of the
+    /// head and tail overflows a `usize`.
+    #[must_use]
+    pub fn new(head: &[usize], tail_len: usize, tail_weight: usize) -> Self {
+        let tail_total = tail_len
+            .checked_mul(tail_weight)
+            .expect("The sum of the weights must fit in a `usize`.");
+        let tail = (tail_total > 0).then(|| Uniform::new(tail_len));
+
+        // The gate chooses between the head buckets and the tail as a whole. If the head is
+        // entirely zero, every sample comes from the tail and no gate is needed.
+        let gate = if tail.is_some() && head.iter().all(|&w| w == 0) {
+            assert!(
+                tail_len >= 2,
+                "The distribution must have at least two non-zero weights."
+            );
+            None
+        } else {
+            let mut weights = head.to_vec();
+            weights.push(tail_total);
+            Some(Generator::new(&weights))
+        };
+
+        Self {
+            gate,
+            head_len: head.len(),
+            tail,
+        }
+    }
+
+    /// The total number of buckets in the head and tail.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.head_len + self.tail.map_or(0, |t| t.len())
+    }
+
+    /// Whether there are no buckets. Always `false`, since two weights must be non-zero.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sample a random bucket index using a given `FairCoin`.
+    pub fn sample(&self, fair_coin: &mut impl FairCoin) -> usize {
+        let i = self
+            .gate
+            .as_ref()
+            .map_or(self.head_len, |gate| gate.sample(fair_coin));
+        match self.tail {
+            Some(tail) if i >= self.head_len => self.head_len + tail.sample(fair_coin),
+            _ => i,
+        }
+    }
+}
+
+/// Samples integers from a union of ranges, each with its own total weight, by first choosing a
+/// range with FLDR and then a uniform offset within it.
+#[derive(Clone)]
+pub struct PiecewiseUniform {
+    segments: Vec<(u64, Uniform)>,
+    generator: Generator,
+}
+
+impl PiecewiseUniform {
+    /// Create a sampler from `(range, weight)` segments, where `weight` is the total weight of the
+    /// whole range. Every integer in a range is equally likely to be chosen once that range is
+    /// chosen. Ranges may overlap, in which case their weights add where they intersect.
+    /// # Panics
+    /// Will panic if a range with a non-zero weight is empty or has more integers than fit in a
+    /// `usize`, or if the weights are invalid for `Generator::new`.
+    #[must_use]
+    pub fn new(segments: &[(Range<u64>, usize)]) -> Self {
+        let generator = Generator::new(&segments.iter().map(|(_, w)| *w).collect::<Vec<_>>());
+        let segments = segments
+            .iter()
+            .map(|(range, w)| {
+                let len = range.end.saturating_sub(range.start);
+                assert!(
+                    *w == 0 || len > 0,
+                    "Every range with a non-zero weight must be non-empty."
+                );
+                let len = usize::try_from(len.max(1))
+                    .expect("The length of every range must fit in a `usize`.");
+                (range.start, Uniform::new(len))
+            })
+            .collect();
+        Self {
+            segments,
+            generator,
+        }
+    }
+
+    /// Sample a random integer from the weighted ranges using a given `FairCoin`.
+    pub fn sample(&self, fair_coin: &mut impl FairCoin) -> u64 {
+        let (start, uniform) = self.segments[self.generator.sample(fair_coin)];
+        start + uniform.sample(fair_coin) as u64
+    }
+}
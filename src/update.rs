@@ -0,0 +1,271 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Changing the weights, and the number of buckets, of a `Generator` without rebuilding its DDG
+//! tree from every weight.
+
+use alloc::sync::Arc;
+
+use crate::{label::Label, levels, Error, Generator, Layout};
+
+/// How an edit of a single weight changes the number of buckets.
+#[derive(Clone, Copy)]
+enum Edit {
+    /// Keep the buckets as they are.
+    Set,
+
+    /// Append the edited bucket after the existing ones.
+    Push,
+
+    /// Remove the edited bucket, whose weight becomes zero.
+    Remove,
+}
+
+impl<L: Label> Generator<L> {
+    /// Change the weight of bucket `index` to `weight`, editing the DDG tree in place of a full
+    /// rebuild. The result is identical to a generator built from the new weights, while other
+    /// clones of the generator keep sampling the old ones.
+    ///
+    /// A weight only has leaves on the levels of its set bits, so while the depth of the tree is
+    /// unchanged, only the levels where a bit of `weight` or of the padding weight flips are edited
+    /// and every other level is copied as is, without examining the remaining weights. An update
+    /// whose new sum of weights crosses a power of two changes the depth, and rebuilds the tree.
+    /// # Panics
+    /// Will panic if `index` is out of bounds, or in the cases that `Generator::try_set_weight`
+    /// returns an `Error`.
+    pub fn set_weight(&mut self, index: usize, weight: usize) {
+        self.try_set_weight(index, weight)
+            .unwrap_or_else(|e| panic!("{e}"));
+    }
+
+    /// Change the weight of bucket `index` like `Generator::set_weight`, but surface invalid
+    /// updates as an `Error` instead of panicking. The generator is unchanged if an error occurs.
+    /// # Errors
+    /// Returns `Error::TooFewNonZeroWeights` if less than two weights would be non-zero,
+    /// `Error::WeightSumOverflow` if the update would push the sum of the weights past a `usize`,
+    /// or `Error::TooManyBuckets` if the tree would need a padding label that does not fit in `L`.
+    /// # Panics
+    /// Will panic if `index` is out of bounds.
+    pub fn try_set_weight(&mut self, index: usize, weight: usize) -> Result<(), Error> {
+        assert!(
+            index < self.bucket_count,
+            "The index must be less than the number of buckets."
+        );
+        self.edit(Edit::Set, index, weight as u128)
+    }
+
+    /// Set the weight of bucket `index` to `weight` while adding or removing a bucket as given by
+    /// `edit`, writing the relabeled levels straight into the new tree.
+    fn edit(&mut self, edit: Edit, index: usize, weight: u128) -> Result<(), Error> {
+        let bucket_count = self.bucket_count;
+        let new_bucket_count = match edit {
+            Edit::Set => bucket_count,
+            Edit::Push => bucket_count + 1,
+            Edit::Remove => bucket_count - 1,
+        };
+
+        // Map a label of the old tree to the new tree. A pushed bucket takes the label of the
+        // padding bucket, which moves up by one, and the buckets above a removed one, and the
+        // padding bucket, move down by one.
+        let relabel = |label: usize| match edit {
+            Edit::Set => label,
+            Edit::Push => label + usize::from(label == bucket_count),
+            Edit::Remove => label - usize::from(label > index),
+        };
+
+        // Read the old weight and padding weight off the levels of the tree. Labels are stored in
+        // increasing order, with the padding label last, so each level is binary searched. A
+        // pushed bucket has no leaves yet, and must not be confused with the padding label.
+        let depth = self.depth();
+        let (mut old_weight, mut old_padding) = (0u128, 0u128);
+        for (j, labels) in levels(&self.level_label_matrix).enumerate() {
+            let bit = 1u128 << (depth - j - 1);
+            if index < bucket_count
+                && labels
+                    .binary_search_by_key(&index, |label| label.to_usize())
+                    .is_ok()
+            {
+                old_weight |= bit;
+            }
+            if labels
+                .last()
+                .is_some_and(|label| label.to_usize() == bucket_count)
+            {
+                old_padding |= bit;
+            }
+        }
+        if weight == old_weight && new_bucket_count == bucket_count {
+            return Ok(());
+        }
+
+        // As when building the tree, wrapping arithmetic recovers the sum even for a depth of 128.
+        let old_sum = 1u128
+            .checked_shl(depth as u32)
+            .unwrap_or(0)
+            .wrapping_sub(old_padding);
+        let sum = (old_sum - old_weight)
+            .checked_add(weight)
+            .filter(|&sum| sum <= old_sum || usize::try_from(sum).is_ok())
+            .ok_or(Error::WeightSumOverflow)?;
+        if weight == 0 && !self.has_other_nonzero_weights(index) {
+            return Err(Error::TooFewNonZeroWeights);
+        }
+
+        let is_power_of_two = sum.is_power_of_two();
+        if sum.ilog2() as usize + usize::from(!is_power_of_two) != depth {
+            let mut weights = self.wide_weights();
+            match edit {
+                Edit::Set => weights[index] = weight,
+                Edit::Push => weights.push(weight),
+                Edit::Remove => {
+                    weights.remove(index);
+                }
+            }
+            let layout = Layout::try_new(&weights)?;
+            if layout.adjusted_bucket_count > L::MAX_BUCKETS {
+                return Err(Error::TooManyBuckets);
+            }
+            *self = Self::from_layout(layout, &weights);
+            return Ok(());
+        }
+
+        let padding = 1u128
+            .checked_shl(depth as u32)
+            .unwrap_or(0)
+            .wrapping_sub(sum);
+        if padding > 0 && new_bucket_count >= L::MAX_BUCKETS {
+            return Err(Error::TooManyBuckets);
+        }
+        let leaves = |w: u128, p: u128| (w.count_ones() + p.count_ones()) as usize;
+        let len = self.level_label_matrix.len() + leaves(weight, padding)
+            - leaves(old_weight, old_padding);
+
+        let mut level_label_matrix: Arc<[L]> = core::iter::repeat_n(L::default(), len).collect();
+        let matrix = Arc::get_mut(&mut level_label_matrix).expect("The matrix was just allocated.");
+        let mut row_start = 0;
+        for (j, labels) in levels(&self.level_label_matrix).enumerate() {
+            let bit = |w: u128| (w >> (depth - j - 1)) & 1 > 0;
+            let row = &mut matrix[row_start..];
+            let mut count = 0;
+            let mut push = |label: usize| {
+                count += 1;
+                row[count] = L::from_usize(label);
+            };
+            if bit(old_weight) == bit(weight) && bit(old_padding) == bit(padding) {
+                labels
+                    .iter()
+                    .for_each(|label| push(relabel(label.to_usize())));
+            } else {
+                // Keep the labels below `index` and above it in place around `index` itself,
+                // and end the level with the padding label.
+                let labels = &labels[..labels.len() - usize::from(bit(old_padding))];
+                let (below, rest) =
+                    labels.split_at(labels.partition_point(|l| l.to_usize() < index));
+                let above = &rest[usize::from(bit(old_weight))..];
+                below
+                    .iter()
+                    .for_each(|label| push(relabel(label.to_usize())));
+                if bit(weight) {
+                    push(index);
+                }
+                above
+                    .iter()
+                    .for_each(|label| push(relabel(label.to_usize())));
+                if bit(padding) {
+                    push(new_bucket_count);
+                }
+            }
+            row[0] = L::from_usize(count);
+            row_start += count + 1;
+        }
+
+        self.replace_matrix(level_label_matrix);
+        self.bucket_count = new_bucket_count;
+        Ok(())
+    }
+
+    /// Append a bucket with weight `weight`, whose index is the previous number of buckets, so
+    /// that the support of the distribution can grow over time.
+    /// # Panics
+    /// Will panic in the cases that `Generator::try_push_weight` returns an `Error`.
+    pub fn push_weight(&mut self, weight: usize) {
+        self.try_push_weight(weight)
+            .unwrap_or_else(|e| panic!("{e}"));
+    }
+
+    /// Append a bucket like `Generator::push_weight`, but surface invalid updates as an `Error`
+    /// instead of panicking. The generator is unchanged if an error occurs.
+    /// # Errors
+    /// Returns `Error::TooManyBuckets` if the labels of the new bucket and the padding bucket would
+    /// not fit in `L`, or `Error::WeightSumOverflow` if the sum of the weights would overflow.
+    pub fn try_push_weight(&mut self, weight: usize) -> Result<(), Error> {
+        if self.bucket_count >= L::MAX_BUCKETS - 1 {
+            return Err(Error::TooManyBuckets);
+        }
+        self.edit(Edit::Push, self.bucket_count, weight as u128)
+    }
+
+    /// Remove bucket `index`, shifting the buckets after it down by one index, so that the support
+    /// of the distribution can shrink over time.
+    /// # Panics
+    /// Will panic if `index` is out of bounds, or if less than two weights would be non-zero.
+    pub fn remove_bucket(&mut self, index: usize) {
+        self.try_remove_bucket(index)
+            .unwrap_or_else(|e| panic!("{e}"));
+    }
+
+    /// Remove a bucket like `Generator::remove_bucket`, but surface invalid updates as an `Error`
+    /// instead of panicking. The generator is unchanged if an error occurs.
+    /// # Errors
+    /// Returns `Error::TooFewNonZeroWeights` if less than two weights would be non-zero.
+    /// # Panics
+    /// Will panic if `index` is out of bounds.
+    pub fn try_remove_bucket(&mut self, index: usize) -> Result<(), Error> {
+        assert!(
+            index < self.bucket_count,
+            "The index must be less than the number of buckets."
+        );
+        self.edit(Edit::Remove, index, 0)
+    }
+
+    /// Replace the DDG tree by an edited copy. With the `zeroize` feature, the old tree is cleared
+    /// first if this generator is its last owner, as it would be when the generator is dropped.
+    fn replace_matrix(&mut self, level_label_matrix: Arc<[L]>) {
+        #[cfg(feature = "zeroize")]
+        if let Some(old) = Arc::get_mut(&mut self.level_label_matrix) {
+            L::zeroize(old);
+        }
+        self.level_label_matrix = level_label_matrix;
+    }
+
+    /// Whether at least two buckets other than `index` have a non-zero weight.
+    fn has_other_nonzero_weights(&self, index: usize) -> bool {
+        let mut others = levels(&self.level_label_matrix)
+            .flatten()
+            .map(|label| label.to_usize())
+            .filter(|&label| label != index && label < self.bucket_count);
+        let Some(first) = others.next() else {
+            return false;
+        };
+        others.any(|label| label != first)
+    }
+}
@@ -0,0 +1,63 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The unsigned integer types that weights may be given in.
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An unsigned integer type that can hold the weights of a distribution, so that weights can be
+/// passed to `Generator::from_weights` at the width they were computed in, e.g. `u64` counts on a
+/// 32-bit target. The DDG tree itself does not depend on the weight type once it is built.
+///
+/// This trait is sealed and implemented for `u8`, `u16`, `u32`, `u64`, `u128`, and `usize`.
+pub trait Weight: Copy + sealed::Sealed {
+    /// The number of bits in the type.
+    const BITS: u32;
+
+    /// Whether the weight is non-zero.
+    fn is_nonzero(self) -> bool;
+
+    /// Widen the weight losslessly.
+    fn to_u128(self) -> u128;
+}
+
+macro_rules! impl_weight {
+    ($($t:ty),*) => {$(
+        impl sealed::Sealed for $t {}
+
+        impl Weight for $t {
+            const BITS: u32 = <$t>::BITS;
+
+            fn is_nonzero(self) -> bool {
+                self != 0
+            }
+
+            fn to_u128(self) -> u128 {
+                self as u128
+            }
+        }
+    )*};
+}
+
+impl_weight!(u8, u16, u32, u64, u128, usize);
@@ -0,0 +1,118 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use fast_loaded_dice_roller as fldr;
+
+#[test]
+fn test_max_depth_is_exact_when_shallow() {
+    let approximation = fldr::Generator::with_max_depth(&[1, 2, 3], 3);
+    assert_eq!(approximation.generator.weights(), [1, 2, 3]);
+    assert_eq!(approximation.max_bias, 0.);
+}
+
+#[test]
+fn test_max_depth_rounds_weights() {
+    let distribution = [1_000_003, 2_000_000, 999_999_997];
+    let approximation = fldr::Generator::with_max_depth(&distribution, 8);
+    let weights = approximation.generator.weights();
+    assert_eq!(weights.iter().sum::<usize>(), 256);
+    assert_eq!(weights, [0, 1, 255]);
+    assert!(approximation.max_bias > 0.);
+    assert!(approximation.max_bias <= 1. / 256.);
+}
+
+#[test]
+fn test_optimal_approximation_keeps_support_under_kl() {
+    use fldr::approximate::Divergence;
+
+    // Largest remainder rounding drops the smallest bucket entirely, which is an infinite
+    // Kullback-Leibler divergence. The optimal approximation must keep it.
+    let distribution = [1, 100_000, 1_000_000];
+    let rounded = fldr::Generator::with_max_depth(&distribution, 6).generator;
+    assert_eq!(rounded.weights()[0], 0);
+
+    let optimal =
+        fldr::Generator::optimal_approximation(&distribution, 6, Divergence::KullbackLeibler);
+    let weights = optimal.generator.weights();
+    assert_eq!(weights.iter().sum::<usize>(), 64);
+    assert!(weights.iter().all(|&w| w > 0), "Weights: {weights:?}");
+
+    // Under total variation, rounding by largest remainder is already optimal.
+    let optimal =
+        fldr::Generator::optimal_approximation(&distribution, 6, Divergence::TotalVariation);
+    assert_eq!(optimal.generator.weights(), rounded.weights());
+}
+
+#[test]
+fn test_from_probabilities() {
+    // Dyadic probabilities are represented exactly.
+    let exact = fldr::Generator::from_probabilities(&[0.5, 0.25, 0.125, 0.125], 3);
+    assert_eq!(exact.generator.weights(), [4, 2, 1, 1]);
+    assert_eq!(exact.max_bias, 0.);
+    assert_eq!(exact.kl_divergence, 0.);
+
+    // Thirds are rounded with the largest remainder method, and need not be normalized.
+    let thirds = fldr::Generator::from_probabilities(&[1., 1., 1.], 4);
+    assert_eq!(thirds.generator.weights(), [6, 5, 5]);
+    assert!((thirds.max_bias - (6. / 16. - 1. / 3.)).abs() < 1e-12);
+    let kl = ((16_f64 / 18.).log2() + 2. * (16_f64 / 15.).log2()) / 3.;
+    assert!((thirds.kl_divergence - kl).abs() < 1e-12);
+
+    // A probability rounded away can never be sampled, which is infinitely distinguishable.
+    let lost = fldr::Generator::from_probabilities(&[1e-9, 0.5, 0.5], 4);
+    assert_eq!(lost.generator.weights(), [0, 8, 8]);
+    assert_eq!(lost.kl_divergence, f64::INFINITY);
+
+    // At full precision the weights still sum to exactly a power of two.
+    let precise = fldr::Generator::from_probabilities(&[0.1, 0.2, 0.3, 0.4], usize::BITS - 1);
+    let weights = precise.generator.weights();
+    assert_eq!(weights.iter().sum::<usize>(), 1 << (usize::BITS - 1));
+    assert!(precise.max_bias < 1e-15);
+}
+
+#[test]
+fn test_optimal_from_probabilities() {
+    use fldr::approximate::Divergence;
+
+    // Naive rounding loses the rare bucket, while the optimum keeps it sampleable.
+    let probabilities = [0.001, 0.499, 0.5];
+    let rounded = fldr::Generator::from_probabilities(&probabilities, 4);
+    assert_eq!(rounded.generator.weights(), [0, 8, 8]);
+    let optimal =
+        fldr::Generator::optimal_from_probabilities(&probabilities, 4, Divergence::KullbackLeibler);
+    let weights = optimal.generator.weights();
+    assert_eq!(weights.iter().sum::<usize>(), 16);
+    assert_eq!(weights[0], 1);
+    assert!(optimal.kl_divergence.is_finite());
+
+    // The optimum is never worse than rounding.
+    let probabilities = [0.013, 0.2, 0.0004, 0.31, 0.07, 0.4066];
+    for precision_bits in 4..12 {
+        let rounded = fldr::Generator::from_probabilities(&probabilities, precision_bits);
+        let optimal = fldr::Generator::optimal_from_probabilities(
+            &probabilities,
+            precision_bits,
+            Divergence::KullbackLeibler,
+        );
+        assert!(optimal.kl_divergence <= rounded.kl_divergence);
+    }
+}
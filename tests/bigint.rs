@@ -0,0 +1,69 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+mod common;
+
+use common::XorShiftCoin;
+use fast_loaded_dice_roller as fldr;
+use num_bigint::BigUint;
+
+#[test]
+fn test_big_weights_round_trip() {
+    // The number of ways to deal a bridge hand with and without any aces, which overflow a `u64`.
+    let choose = |n: u32, k: u32| -> BigUint {
+        (0..k).fold(BigUint::from(1u8), |c, i| c * (n - i) / (i + 1))
+    };
+    let without_aces = choose(48, 13);
+    let with_aces = choose(52, 13) - &without_aces;
+    let weights = [without_aces, with_aces, BigUint::default()];
+
+    let generator = fldr::Generator::from_big_weights(&weights);
+    assert_eq!(generator.big_weights(), weights);
+
+    let mut coin = XorShiftCoin::new(47);
+    let hands_without_aces = (0..20_000)
+        .filter(|_| generator.sample(&mut coin) == 0)
+        .count();
+
+    // About 30.4% of hands have no aces.
+    assert!((5_800..6_400).contains(&hands_without_aces));
+}
+
+#[test]
+fn test_big_weights_match_small_weights() {
+    let small = fldr::Generator::new(&[3, 0, 7, 1, 5]);
+    let big = fldr::Generator::from_big_weights(&[3u8, 0, 7, 1, 5].map(BigUint::from));
+    assert_eq!(big.weights(), small.weights());
+
+    let (mut a, mut b) = (XorShiftCoin::new(53), XorShiftCoin::new(53));
+    for _ in 0..1_000 {
+        assert_eq!(small.sample(&mut a), big.sample(&mut b));
+    }
+
+    // Weights of a thousand bits build a thousand-level tree.
+    let huge: BigUint = BigUint::from(1u8) << 1_000;
+    let deep = fldr::Generator::from_big_weights(&[huge.clone(), huge + 1u8]);
+    assert!(deep.sample(&mut a) < 2);
+    assert_eq!(
+        fldr::Generator::try_from_big_weights(&[BigUint::from(4u8)]).err(),
+        Some(fldr::Error::TooFewNonZeroWeights)
+    );
+}
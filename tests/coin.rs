@@ -0,0 +1,206 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+mod common;
+
+use common::XorShiftCoin;
+use fast_loaded_dice_roller as fldr;
+use fldr::FairCoin;
+
+/// A coin that always lands on the same side.
+struct StuckCoin(bool);
+
+impl FairCoin for StuckCoin {
+    fn flip(&mut self) -> bool {
+        self.0
+    }
+}
+
+/// A coin that repeats heads, heads, heads, tails.
+struct PatternCoin(usize);
+
+impl FairCoin for PatternCoin {
+    fn flip(&mut self) -> bool {
+        self.0 += 1;
+        !self.0.is_multiple_of(4)
+    }
+}
+
+#[test]
+fn test_combined_coin_masks_stuck_source() {
+    // XOR with a stuck source reproduces (or inverts) the healthy source bit for bit.
+    let mut healthy = XorShiftCoin::new(9);
+    let mut combined = fldr::coin::CombinedCoin::new(XorShiftCoin::new(9), StuckCoin(true));
+    for _ in 0..1_000 {
+        assert_eq!(combined.flip(), !healthy.flip());
+    }
+}
+
+#[test]
+fn test_region_coin_is_location_stable() {
+    let generator = fldr::Generator::new(&[5, 3, 1, 1]);
+    let world = |seed| -> Vec<usize> {
+        (-20..20)
+            .flat_map(|x| (-20..20).map(move |y| (x, y)))
+            .map(|(x, y)| generator.sample_region(seed, &[x, y], 0))
+            .collect()
+    };
+
+    // The same world is generated identically, and a different seed gives a different world.
+    let a = world(1);
+    assert_eq!(a, world(1));
+    assert_ne!(a, world(2));
+
+    // Regions are visited in reverse order without affecting their choices.
+    assert_eq!(generator.sample_region(1, &[19, 19], 0), a[a.len() - 1]);
+    assert_ne!(
+        (0..64)
+            .map(|salt| generator.sample_region(1, &[0, 0], salt))
+            .collect::<Vec<_>>(),
+        vec![a[20 * 40 + 20]; 64]
+    );
+
+    // The mix of choices follows the weights.
+    let heavy = a.iter().filter(|&&i| i == 0).count() as f64 / a.len() as f64;
+    assert!((heavy - 0.5).abs() < 0.05, "{heavy}");
+}
+
+#[test]
+fn test_health_checked_coin() {
+    use fldr::coin::{HealthCheckedCoin, HealthFailure};
+
+    // A healthy source passes both tests.
+    let mut healthy = HealthCheckedCoin::new(XorShiftCoin::new(3));
+    let generator = fldr::Generator::new(&[1, 2, 3]);
+    for _ in 0..100_000 {
+        generator.sample(&mut healthy);
+    }
+    assert_eq!(healthy.check(), Ok(()));
+
+    // A stuck source trips the repetition count test after exactly the cutoff.
+    let mut stuck = HealthCheckedCoin::new(StuckCoin(false));
+    for _ in 0..20 {
+        stuck.flip();
+    }
+    assert_eq!(stuck.failure(), None);
+    stuck.flip();
+    assert_eq!(stuck.failure(), Some(HealthFailure::RepetitionCount));
+    assert_eq!(stuck.check(), Err(fldr::Error::UnhealthyCoin));
+    stuck.reset();
+    assert_eq!(stuck.failure(), None);
+
+    // A source that lands heads three times in four never repeats for long, but is biased.
+    let mut biased = HealthCheckedCoin::new(PatternCoin(0));
+    for _ in 0..HealthCheckedCoin::<StuckCoin>::WINDOW {
+        biased.flip();
+    }
+    assert_eq!(biased.failure(), Some(HealthFailure::AdaptiveProportion));
+}
+
+/// A coin that lands heads three times in four, independently on every flip.
+struct BiasedCoin(XorShiftCoin);
+
+impl FairCoin for BiasedCoin {
+    fn flip(&mut self) -> bool {
+        self.0.flip() | self.0.flip()
+    }
+}
+
+#[test]
+fn test_debiased_coin() {
+    use fldr::coin::{CountingCoin, DebiasedCoin};
+
+    const FLIPS: usize = 100_000;
+    let mut raw_flips = Vec::new();
+    for iterations in [0, 4] {
+        let mut source = BiasedCoin(XorShiftCoin::new(17));
+        let mut debiased = DebiasedCoin::iterated(CountingCoin::new(&mut source), iterations);
+        let heads = (0..FLIPS).filter(|_| debiased.flip()).count();
+        assert!(heads.abs_diff(FLIPS / 2) < 1_000, "Heads: {heads}");
+        raw_flips.push(debiased.into_inner().flips());
+    }
+
+    // Plain extraction keeps 3/8 of the pairs, i.e. uses 16/3 source flips per fair flip, and
+    // iterating extracts more from the same source.
+    let plain = raw_flips[0] as f64 / FLIPS as f64;
+    assert!((plain - 16. / 3.).abs() < 0.1, "{plain}");
+    assert!(raw_flips[1] * 3 < raw_flips[0] * 2, "{raw_flips:?}");
+}
+
+#[test]
+fn test_fn_coin() {
+    // Every leaf of a uniform tree of four is on the second level, so each pair of flips spells
+    // out the sampled bucket in binary.
+    let mut script = [false, true, true, true].into_iter();
+    let mut coin = fldr::coin::FnCoin::new(|| script.next().unwrap());
+    let generator = fldr::Generator::new(&[1, 1, 1, 1]);
+    assert_eq!(generator.sample(&mut coin), 1);
+    assert_eq!(generator.sample(&mut coin), 3);
+}
+
+#[test]
+fn test_iter_coin() {
+    // The bits of the tape 0b0111 are read least significant first.
+    let tape = [0b0111_u8];
+    let mut coin = fldr::coin::IterCoin::new(
+        tape.iter()
+            .flat_map(|b| (0..8).map(move |i| b >> i & 1 == 1)),
+    );
+    let generator = fldr::Generator::new(&[1, 1, 1, 1]);
+    assert_eq!(generator.sample(&mut coin), 3);
+    assert_eq!(generator.sample(&mut coin), 2);
+    assert_eq!(coin.flips(), 4);
+    assert_eq!(coin.into_inner().count(), 4);
+}
+
+#[test]
+#[should_panic(expected = "The iterator of flips was exhausted after 3 flips.")]
+fn test_iter_coin_exhausted() {
+    let mut coin = fldr::coin::IterCoin::new([true, false, true]);
+    let generator = fldr::Generator::new(&[1, 1, 1, 1]);
+    generator.sample(&mut coin);
+    generator.sample(&mut coin);
+}
+
+#[test]
+fn test_borrowed_and_boxed_coins() {
+    let generator = fldr::Generator::new(&[5, 3, 1, 1]);
+    let expected: Vec<usize> = {
+        let mut coin = XorShiftCoin::new(29);
+        (0..100).map(|_| generator.sample(&mut coin)).collect()
+    };
+
+    // A boxed trait object samples the same stream as the coin it erases.
+    let mut boxed: Box<dyn FairCoin> = Box::new(XorShiftCoin::new(29));
+    let samples: Vec<usize> = (0..100).map(|_| generator.sample(&mut boxed)).collect();
+    assert_eq!(samples, expected);
+
+    // A borrowed coin can be moved into a wrapper and keeps its state once the wrapper is gone.
+    let mut coin = XorShiftCoin::new(29);
+    let mut samples: Vec<usize> = {
+        let mut combined = fldr::coin::CombinedCoin::new(&mut coin, StuckCoin(false));
+        (0..50).map(|_| generator.sample(&mut combined)).collect()
+    };
+    let mut borrowed: &mut dyn FairCoin = &mut coin;
+    samples.extend((0..50).map(|_| generator.sample(&mut borrowed)));
+    assert_eq!(samples, expected);
+}
@@ -0,0 +1,84 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use fast_loaded_dice_roller as fldr;
+use fldr::constant_time::ConstantTimeSampler;
+use rand_chacha::ChaCha8Rng;
+use rand_core::SeedableRng;
+
+#[test]
+fn test_constant_time_sampler() {
+    let generator = fldr::Generator::new(&[1, 2, 3]);
+    let sampler = ConstantTimeSampler::with_security_bits(&generator, 128);
+
+    // The padding weight is 2 of 8, so each pass is rejected with probability 2^-2.
+    assert_eq!(sampler.passes(), 64);
+    assert_eq!(sampler.flips_per_sample(), 64 * generator.depth());
+
+    let mut coin = fldr::rand_core::RngCoin::new(ChaCha8Rng::seed_from_u64(3));
+    let mut histogram = [0_usize; 3];
+    for _ in 0..60_000 {
+        histogram[sampler.sample(&mut coin).unwrap()] += 1;
+    }
+    for (count, expected) in histogram.into_iter().zip([10_000, 20_000, 30_000]) {
+        assert!(count.abs_diff(expected) < 600);
+    }
+}
+
+#[test]
+fn test_constant_time_rejection() {
+    // Without padding a single pass always succeeds.
+    let uniform = fldr::Generator::new(&[1, 1, 1, 1]);
+    assert_eq!(
+        ConstantTimeSampler::with_security_bits(&uniform, 128).passes(),
+        1
+    );
+
+    // A single pass on three equal weights is rejected a quarter of the time.
+    let sampler = ConstantTimeSampler::new(&fldr::Generator::new(&[1, 1, 1]), 1);
+    let mut coin = fldr::rand_core::RngCoin::new(ChaCha8Rng::seed_from_u64(5));
+    let failures = (0..40_000)
+        .filter(|_| sampler.sample(&mut coin).is_none())
+        .count();
+    assert!((9_500..10_500).contains(&failures));
+}
+
+#[test]
+fn test_constant_time_decoded_tree() {
+    // A pass through the tree of a decoded generator over weights 3 and 2 is rejected with
+    // probability 3/8, so 91 passes are the fewest whose failure probability is at most 2^-128.
+    let bytes = fldr::Generator::new(&[3, 2]).to_bytes();
+    let generator = fldr::Generator::from_bytes(&bytes).unwrap();
+    assert_eq!(generator.padding_weight(), 3);
+    let sampler = ConstantTimeSampler::with_security_bits(&generator, 128);
+    assert_eq!(sampler.passes(), 91);
+    assert_eq!(
+        ConstantTimeSampler::with_security_bits(&generator, 0).passes(),
+        1
+    );
+
+    let mut coin = fldr::rand_core::RngCoin::new(ChaCha8Rng::seed_from_u64(7));
+    let heavy = (0..50_000)
+        .filter(|_| sampler.sample(&mut coin).unwrap() == 0)
+        .count();
+    assert!((29_300..30_700).contains(&heavy), "{heavy}");
+}
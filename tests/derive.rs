@@ -0,0 +1,56 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+mod common;
+
+use common::XorShiftCoin;
+use fast_loaded_dice_roller as fldr;
+use fldr::WeightedSample;
+
+#[derive(Clone, Copy, Debug, PartialEq, WeightedSample)]
+enum Loot {
+    #[weight = 60]
+    Gold,
+    #[weight = 0]
+    Nothing,
+    #[weight = 39]
+    Potion,
+    #[weight = 1]
+    Sword,
+}
+
+#[test]
+fn test_derive_weighted_sample() {
+    assert_eq!(Loot::WEIGHTS, [60, 0, 39, 1]);
+    assert_eq!(Loot::from_index(2), Loot::Potion);
+
+    // The sampler makes the same choices as a generator over the weights of the variants.
+    let loot = Loot::sampler();
+    let generator = fldr::Generator::new(Loot::WEIGHTS);
+    let variants = [Loot::Gold, Loot::Nothing, Loot::Potion, Loot::Sword];
+    let mut a = XorShiftCoin::new(12);
+    let mut b = XorShiftCoin::new(12);
+    for _ in 0..10_000 {
+        let drop = loot.sample(&mut a);
+        assert_eq!(drop, variants[generator.sample(&mut b)]);
+        assert_ne!(drop, Loot::Nothing);
+    }
+}
@@ -0,0 +1,54 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+mod common;
+
+use common::XorShiftCoin;
+use fast_loaded_dice_roller as fldr;
+use fldr::distance;
+
+#[test]
+fn test_distances_to_exact_pmf() {
+    let generator = fldr::Generator::new(&[1, 3, 0, 4]);
+    let exact = [0.125, 0.375, 0., 0.5];
+    assert_eq!(distance::total_variation(&generator, &exact), 0.);
+    assert_eq!(distance::kl_divergence(&generator, &exact), 0.);
+
+    let uniform = [0.25; 4];
+    assert_eq!(distance::total_variation(&generator, &uniform), 0.375);
+    assert!(distance::kl_divergence(&generator, &[0.5, 0.5, 0., 0.]).is_infinite());
+}
+
+#[test]
+fn test_chi_square_of_samples() {
+    let generator = fldr::Generator::new(&[1, 3, 0, 4]);
+    let mut coin = XorShiftCoin::new(12);
+    let mut histogram = [0; 4];
+    for _ in 0..100_000 {
+        histogram[generator.sample(&mut coin)] += 1;
+    }
+
+    // The 99.9th percentile of the chi-square distribution with two degrees of freedom.
+    assert!(distance::chi_square(&generator, &histogram) < 13.82);
+    assert!(distance::total_variation(&generator, &distance::empirical(&histogram)) < 0.01);
+    assert!(distance::chi_square(&generator, &[1, 1, 1, 1]).is_infinite());
+}
@@ -0,0 +1,100 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+mod common;
+
+use common::XorShiftCoin;
+use fast_loaded_dice_roller as fldr;
+
+const ROLL_COUNT: usize = 200_000;
+
+/// The sample mean of `ROLL_COUNT` draws from `generator`.
+fn sample_mean(generator: &fldr::Generator) -> f64 {
+    let mut coin = XorShiftCoin::new(21);
+    let total: usize = (0..ROLL_COUNT).map(|_| generator.sample(&mut coin)).sum();
+    total as f64 / ROLL_COUNT as f64
+}
+
+#[test]
+fn test_binomial() {
+    let (offset, generator) = fldr::distributions::binomial(20, 0.3, 32);
+    assert_eq!(offset, 0);
+    let mean = offset as f64 + sample_mean(&generator);
+    assert!((mean - 6.).abs() < 0.05, "Mean: {mean}");
+
+    // Only the outcomes within about 7 standard deviations of the mean of 5e7 are kept.
+    let (offset, generator) = fldr::distributions::binomial(100_000_000, 0.5, 32);
+    assert!(offset > 49_960_000 && generator.weights().len() < 80_000);
+    let mean = offset as f64 + sample_mean(&generator);
+    assert!((mean - 5e7).abs() < 50., "Mean: {mean}");
+}
+
+#[test]
+fn test_geometric() {
+    let generator = fldr::distributions::geometric(0.25, 32);
+    let mean = sample_mean(&generator);
+    assert!((mean - 3.).abs() < 0.05, "Mean: {mean}");
+}
+
+#[test]
+fn test_poisson() {
+    let generator = fldr::distributions::poisson(4.5, 32);
+    let mean = sample_mean(&generator);
+    assert!((mean - 4.5).abs() < 0.05, "Mean: {mean}");
+}
+
+#[test]
+#[should_panic(expected = "The probability must be in (0, 1).")]
+fn test_degenerate_binomial() {
+    let _generator = fldr::distributions::binomial(10, 1., 16);
+}
+
+#[test]
+fn test_discrete_gaussian() {
+    let gaussian = fldr::discrete_gaussian::DiscreteGaussian::new(3.2, 0.5, 6., 40);
+
+    // The cut keeps the integers within 19.2 of the center.
+    assert_eq!(gaussian.support(), -18..=19);
+
+    let mut coin = XorShiftCoin::new(23);
+    let samples: Vec<i64> = (0..ROLL_COUNT)
+        .map(|_| gaussian.sample(&mut coin))
+        .collect();
+    let mean = samples.iter().sum::<i64>() as f64 / ROLL_COUNT as f64;
+    let variance = samples
+        .iter()
+        .map(|&x| (x as f64 - mean).powi(2))
+        .sum::<f64>()
+        / ROLL_COUNT as f64;
+    assert!((mean - 0.5).abs() < 0.05, "Mean: {mean}");
+    assert!(
+        (variance - 3.2_f64.powi(2)).abs() < 0.2,
+        "Variance: {variance}"
+    );
+}
+
+#[test]
+fn test_discrete_gaussian_drops_rounded_tails() {
+    // Masses beyond about 4.6 sigma round to zero with 16 bits of precision.
+    let gaussian = fldr::discrete_gaussian::DiscreteGaussian::new(1., 0., 10., 16);
+    assert_eq!(gaussian.support(), -4..=4);
+}
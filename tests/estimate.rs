@@ -0,0 +1,53 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+mod common;
+
+use common::XorShiftCoin;
+use fast_loaded_dice_roller as fldr;
+use fldr::estimate::Estimate;
+
+#[test]
+fn test_estimate_recovers_weights() {
+    let weights = [1, 3, 0, 4];
+    let generator = fldr::Generator::new(&weights);
+    let mut coin = XorShiftCoin::new(31);
+    let histogram = generator.sample_histogram(&mut coin, 50_000);
+
+    let estimate = Estimate::new(histogram.counts(), 0.999);
+    assert_eq!(estimate.weights(), histogram.counts());
+    for (i, &(lower, upper)) in estimate.intervals().iter().enumerate() {
+        let p = weights[i] as f64 / 8.;
+        assert!(lower <= p && p <= upper, "{p} not in ({lower}, {upper})");
+        assert!(lower <= estimate.probabilities()[i] && estimate.probabilities()[i] <= upper);
+    }
+    assert_eq!(estimate.to_generator().weights(), histogram.counts());
+}
+
+#[test]
+fn test_normal_interval_width() {
+    // With 100 of 400 samples, the 95% Wilson interval is approximately (0.210, 0.295).
+    let estimate = Estimate::new(&[100, 300], 0.95);
+    let (lower, upper) = estimate.intervals()[0];
+    assert!((lower - 0.2104).abs() < 1e-3, "Lower: {lower}");
+    assert!((upper - 0.2946).abs() < 1e-3, "Upper: {upper}");
+}
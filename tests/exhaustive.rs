@@ -0,0 +1,157 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Exhaustive golden tests over every small distribution.
+//!
+//! A DDG tree whose weights sum to a power of two has no back-edges, so enumerating every bit
+//! stream of length `depth` visits every leaf and gives the exact output probabilities. For other
+//! sums, the tree is the tree of the padded distribution with the padding label replaced by a
+//! back-edge to the root. These tests check both halves of that statement for every distribution
+//! with at most `MAX_BUCKETS` buckets and a total weight of at most `MAX_SUM`.
+
+mod common;
+
+use common::XorShiftCoin;
+use fast_loaded_dice_roller as fldr;
+
+const MAX_SUM: usize = 16;
+const MAX_BUCKETS: usize = 5;
+
+/// A coin that replays a fixed tape of bits and then falls back to a deterministic stream,
+/// counting the number of flips consumed.
+struct TapeCoin {
+    tape: Vec<bool>,
+    flips: usize,
+    fallback: XorShiftCoin,
+}
+
+impl TapeCoin {
+    fn new(tape: Vec<bool>) -> Self {
+        Self {
+            tape,
+            flips: 0,
+            fallback: XorShiftCoin::new(0x5EED),
+        }
+    }
+}
+
+impl fldr::FairCoin for TapeCoin {
+    fn flip(&mut self) -> bool {
+        let b = self
+            .tape
+            .get(self.flips)
+            .copied()
+            .unwrap_or_else(|| self.fallback.flip());
+        self.flips += 1;
+        b
+    }
+}
+
+/// The bits of `s`, most significant first, as a tape of length `len`.
+fn tape(s: usize, len: usize) -> Vec<bool> {
+    (0..len).rev().map(|b| (s >> b) & 1 > 0).collect()
+}
+
+/// Sample once from `generator` using `tape` and report the label and the number of flips used.
+fn run(generator: &fldr::Generator, tape: Vec<bool>) -> (usize, usize) {
+    let mut coin = TapeCoin::new(tape);
+    let label = generator.sample(&mut coin);
+    (label, coin.flips)
+}
+
+/// Enumerate every distribution of exactly `len` weights whose sum is at most `MAX_SUM`.
+fn distributions(len: usize) -> Vec<Vec<usize>> {
+    fn extend(prefix: &mut Vec<usize>, len: usize, budget: usize, out: &mut Vec<Vec<usize>>) {
+        if prefix.len() == len {
+            out.push(prefix.clone());
+            return;
+        }
+        for w in 0..=budget {
+            prefix.push(w);
+            extend(prefix, len, budget - w, out);
+            prefix.pop();
+        }
+    }
+
+    let mut out = Vec::new();
+    extend(&mut Vec::new(), len, MAX_SUM, &mut out);
+    out.retain(|d| d.iter().filter(|&&w| w > 0).count() >= 2);
+    out
+}
+
+/// Check the exact output probabilities of the generator for `distribution`.
+fn check_exact(distribution: &[usize]) {
+    let sum: usize = distribution.iter().sum();
+    let depth = sum.next_power_of_two().ilog2() as usize;
+    let padding = (1 << depth) - sum;
+
+    let generator = fldr::Generator::new(distribution);
+    let padded_distribution: Vec<_> = distribution
+        .iter()
+        .copied()
+        .chain((padding > 0).then_some(padding))
+        .collect();
+    let padded = fldr::Generator::new(&padded_distribution);
+
+    // Every leaf at depth `m` is reached by `2^(depth - m)` of the `2^depth` tapes, so the counts
+    // must reproduce the padded weights exactly.
+    let mut counts = vec![0; padded_distribution.len()];
+    for s in 0..1 << depth {
+        let (label, flips) = run(&padded, tape(s, depth));
+        assert!(flips <= depth, "{distribution:?}: no back-edges expected");
+        counts[label] += 1;
+
+        if label < distribution.len() {
+            // The first pass of the original tree must reach the same leaf.
+            assert_eq!(
+                run(&generator, tape(s, depth)),
+                (label, flips),
+                "{distribution:?}: first pass disagrees on tape {s:0depth$b}"
+            );
+        } else {
+            // The padding leaf must be a back-edge, after which sampling starts over at the root.
+            for c in 0..1 << depth {
+                let mut restarted = tape(s, depth)[..flips].to_vec();
+                restarted.extend(tape(c, depth));
+                let (expected_label, expected_flips) = run(&generator, tape(c, depth));
+                assert_eq!(
+                    run(&generator, restarted),
+                    (expected_label, flips + expected_flips),
+                    "{distribution:?}: back-edge on tape {s:0depth$b} did not restart"
+                );
+            }
+        }
+    }
+    assert_eq!(
+        counts, padded_distribution,
+        "{distribution:?}: inexact tree"
+    );
+}
+
+#[test]
+fn test_all_small_distributions() {
+    for len in 2..=MAX_BUCKETS {
+        for distribution in distributions(len) {
+            check_exact(&distribution);
+        }
+    }
+}
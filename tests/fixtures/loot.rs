@@ -0,0 +1,12 @@
+// Generated by fast_loaded_dice_roller::codegen for the weights [60, 25, 10, 5].
+static LOOT_MATRIX: [usize; 21] = [
+    0,
+    1, 0,
+    3, 0, 1, 4,
+    4, 0, 1, 2, 4,
+    3, 0, 3, 4,
+    1, 2,
+    2, 1, 3,
+];
+pub static LOOT: fast_loaded_dice_roller::codegen::StaticGenerator<'static> =
+    fast_loaded_dice_roller::codegen::StaticGenerator::from_raw_parts(4, &LOOT_MATRIX);
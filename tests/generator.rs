@@ -0,0 +1,904 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+mod common;
+
+use common::XorShiftCoin;
+use fast_loaded_dice_roller as fldr;
+
+#[test]
+fn test_clone_shares_samples_across_threads() {
+    let generator = fldr::Generator::new(&[5, 0, 12, 3, 9]);
+
+    // Each thread receives a cheap clone and must reproduce the original's sample stream.
+    let mut coin = XorShiftCoin::new(3);
+    let expected: Vec<_> = (0..1_000).map(|_| generator.sample(&mut coin)).collect();
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let generator = generator.clone();
+            std::thread::spawn(move || {
+                let mut coin = XorShiftCoin::new(3);
+                (0..1_000)
+                    .map(|_| generator.sample(&mut coin))
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_full_width_sum() {
+    // A sum just above `2^(usize::BITS - 1)` requires a tree as deep as `usize` is wide.
+    let half = 1usize << (usize::BITS - 2);
+    let generator = fldr::Generator::new(&[half, half + 1, 0, half]);
+
+    let mut coin = XorShiftCoin::new(5);
+    for _ in 0..1_000 {
+        assert_ne!(generator.sample(&mut coin), 2);
+    }
+}
+
+#[test]
+fn test_weights_round_trip() {
+    for distribution in [
+        vec![5, 0, 12, 3, 9],
+        vec![1, 1, 2, 4],
+        vec![0, 7, 0, 1000, 3],
+    ] {
+        assert_eq!(fldr::Generator::new(&distribution).weights(), distribution);
+    }
+}
+
+#[test]
+fn test_restrict() {
+    let generator = fldr::Generator::new(&[10, 20, 30, 40, 50]);
+    let restricted = generator.restrict(2..=3);
+    assert_eq!(restricted.weights(), [0, 0, 30, 40, 0]);
+
+    let mut coin = XorShiftCoin::new(8);
+    for _ in 0..1_000 {
+        assert!((2..=3).contains(&restricted.sample(&mut coin)));
+    }
+}
+
+#[test]
+fn test_sample_histogram() {
+    let generator = fldr::Generator::new(&[1, 0, 6, 1]);
+    let mut coin = XorShiftCoin::new(2);
+    let mut histogram = generator.sample_histogram(&mut coin, 8_000);
+    assert_eq!(histogram.total(), 8_000);
+    assert_eq!(histogram.counts()[1], 0);
+    assert_eq!(histogram.mode(), Some(2));
+    let mean = histogram.mean_index().unwrap();
+    assert!((mean - 1.875).abs() < 0.05, "Mean: {mean}");
+
+    histogram.merge(&fldr::histogram::Histogram::from(vec![0, 0, 0, 0, 5]));
+    assert_eq!(histogram.counts().len(), 5);
+    assert_eq!(histogram.total(), 8_005);
+    assert_eq!(histogram.to_string().lines().count(), 5);
+}
+
+#[test]
+fn test_sample_n_sorted() {
+    let generator = fldr::Generator::new(&[5, 0, 3, 2]);
+    let samples = generator.sample_n_sorted(&mut XorShiftCoin::new(33), 10_000);
+    assert_eq!(samples.len(), 10_000);
+    assert!(samples.windows(2).all(|w| w[0] <= w[1]));
+    assert!(!samples.contains(&1));
+
+    // The same counts are drawn as by `sample_multinomial`, and expanded in order.
+    let counts = generator.sample_multinomial(&mut XorShiftCoin::new(33), 10_000);
+    for (i, &count) in counts.iter().enumerate() {
+        assert_eq!(samples.iter().filter(|&&s| s == i).count(), count);
+    }
+
+    // Enough draws to split them across the tree are still sorted and follow the weights.
+    let samples = generator.sample_n_sorted(&mut XorShiftCoin::new(34), 1_000_000);
+    assert_eq!(samples.len(), 1_000_000);
+    assert!(samples.windows(2).all(|w| w[0] <= w[1]));
+    let first_heavy = samples.partition_point(|&s| s < 2);
+    assert!(first_heavy.abs_diff(500_000) < 2_500, "{first_heavy}");
+}
+
+#[test]
+fn test_sample_iter() {
+    let generator = fldr::Generator::new(&[5, 0, 3, 2]);
+    let mut coin = XorShiftCoin::new(8);
+    let samples: Vec<usize> = generator.sample_iter(&mut coin).take(1_000).collect();
+    assert_eq!(samples.len(), 1_000);
+    assert!(!samples.contains(&1));
+
+    // The iterator makes the same draws as repeated calls to `sample`.
+    let mut coin = XorShiftCoin::new(8);
+    assert!(samples.iter().all(|&s| s == generator.sample(&mut coin)));
+}
+
+#[test]
+fn test_sample_fill() {
+    let generator = fldr::Generator::new(&[5, 0, 3, 2]);
+    let mut out = [usize::MAX; 500];
+    generator.sample_fill(&mut XorShiftCoin::new(21), &mut out);
+
+    // Filling makes the same draws as repeated calls to `sample`, as does `sample_n`.
+    let mut coin = XorShiftCoin::new(21);
+    assert!(out.iter().all(|&s| s == generator.sample(&mut coin)));
+    assert_eq!(generator.sample_n(&mut XorShiftCoin::new(21), 500), out);
+    assert!(generator.sample_n(&mut XorShiftCoin::new(21), 0).is_empty());
+}
+
+#[test]
+fn test_labeled_generator() {
+    let loot = fldr::labeled::LabeledGenerator::new([
+        (String::from("sword"), 1),
+        (String::from("shield"), 0),
+        (String::from("potion"), 3),
+    ]);
+    assert_eq!(loot.items().len(), 3);
+    assert_eq!(loot.generator().weights(), [1, 0, 3]);
+
+    let mut a = XorShiftCoin::new(6);
+    let mut b = XorShiftCoin::new(6);
+    let mut potions = 0;
+    for _ in 0..4_000 {
+        let item = loot.sample(&mut a);
+        assert_eq!(item, &loot.items()[loot.sample_index(&mut b)]);
+        assert_ne!(item, "shield");
+        potions += usize::from(item == "potion");
+    }
+    assert!(potions.abs_diff(3_000) < 150, "{potions}");
+
+    assert_eq!(
+        fldr::labeled::LabeledGenerator::try_new([('a', 1)]).err(),
+        Some(fldr::Error::TooFewNonZeroWeights)
+    );
+}
+
+#[test]
+fn test_from_map() {
+    let counts = std::collections::HashMap::from([("the", 6), ("a", 2), ("never", 0)]);
+    let tokens = fldr::Generator::from_map(&counts);
+    let mut coin = XorShiftCoin::new(15);
+    let mut the = 0;
+    for _ in 0..8_000 {
+        let token = tokens.sample(&mut coin);
+        assert_ne!(*token, "never");
+        the += usize::from(*token == "the");
+    }
+    assert!(the.abs_diff(6_000) < 200, "{the}");
+
+    // Each key keeps its own weight, whatever order the map iterates in.
+    for (key, weight) in tokens.items().iter().zip(tokens.generator().weights()) {
+        assert_eq!(counts[key], weight);
+    }
+}
+
+#[test]
+fn test_set_weight() {
+    let mut weights = vec![5, 0, 3, 2, 9, 1, 0, 7];
+    let mut generator = fldr::Generator::new(&weights);
+    let mut compact: fldr::Generator<u16> =
+        fldr::Generator::builder().build_compact(&weights).unwrap();
+    let mut coin = XorShiftCoin::new(19);
+    for step in 0..500 {
+        // Mostly small changes, with occasional large ones that change the depth of the tree.
+        let index = generator.sample(&mut coin) ^ (step % weights.len());
+        let weight = if step % 7 == 0 {
+            fldr::Generator::new_uniform(1_000).sample(&mut coin)
+        } else {
+            fldr::Generator::new_uniform(16).sample(&mut coin)
+        };
+        let mut expected = weights.clone();
+        expected[index] = weight;
+        if expected.iter().filter(|&&w| w > 0).count() < 2 {
+            assert_eq!(
+                generator.try_set_weight(index, weight),
+                Err(fldr::Error::TooFewNonZeroWeights)
+            );
+            assert_eq!(generator.weights(), weights);
+            continue;
+        }
+        weights = expected;
+        generator.set_weight(index, weight);
+        compact.set_weight(index, weight);
+        assert_eq!(generator.weights(), weights);
+
+        // The edited tree samples exactly like a tree built from the new weights.
+        let fresh = fldr::Generator::new(&weights);
+        let mut a = XorShiftCoin::new(step as u64);
+        let mut b = XorShiftCoin::new(step as u64);
+        let mut c = XorShiftCoin::new(step as u64);
+        for _ in 0..20 {
+            let i = fresh.sample(&mut a);
+            assert_eq!(i, generator.sample(&mut b));
+            assert_eq!(i, compact.sample(&mut c));
+        }
+    }
+
+    // Clones keep the weights they were made with.
+    let snapshot = generator.clone();
+    generator.set_weight(0, weights[0] + 1);
+    assert_eq!(snapshot.weights(), weights);
+}
+
+#[test]
+fn test_push_and_remove_buckets() {
+    let mut weights = vec![5, 3];
+    let mut generator = fldr::Generator::new(&weights);
+    let mut coin = XorShiftCoin::new(23);
+    for step in 0..300 {
+        if step % 3 == 2 && weights.len() > 2 {
+            let index = fldr::Generator::new_uniform(weights.len()).sample(&mut coin);
+            let mut expected = weights.clone();
+            expected.remove(index);
+            if expected.iter().filter(|&&w| w > 0).count() < 2 {
+                assert_eq!(
+                    generator.try_remove_bucket(index),
+                    Err(fldr::Error::TooFewNonZeroWeights)
+                );
+            } else {
+                generator.remove_bucket(index);
+                weights = expected;
+            }
+        } else {
+            let weight = fldr::Generator::new_uniform(40).sample(&mut coin);
+            generator.push_weight(weight);
+            weights.push(weight);
+        }
+        assert_eq!(generator.weights(), weights);
+
+        // The edited tree samples exactly like a tree built from the new weights.
+        let fresh = fldr::Generator::new(&weights);
+        let mut a = XorShiftCoin::new(step);
+        let mut b = XorShiftCoin::new(step);
+        for _ in 0..20 {
+            assert_eq!(fresh.sample(&mut a), generator.sample(&mut b));
+        }
+    }
+
+    // A compact tree must leave room for the label of the padding bucket.
+    let mut compact: fldr::Generator<u16> = fldr::Generator::builder()
+        .build_compact(&vec![1; 65_533])
+        .unwrap();
+    assert_eq!(compact.try_push_weight(1), Ok(()));
+    assert_eq!(compact.try_push_weight(1), Err(fldr::Error::TooManyBuckets));
+    assert_eq!(compact.weights(), vec![1; 65_534]);
+}
+
+#[test]
+fn test_distribution_properties() {
+    let generator = fldr::Generator::new(&[2, 0, 6, 6, 1]);
+    assert!(!generator.is_uniform());
+    assert_eq!(generator.argmax(), 2);
+    assert_eq!(generator.min_nonzero_probability(), 1. / 15.);
+    assert_eq!(generator.support(), [0, 2, 3, 4]);
+
+    assert!(fldr::Generator::new(&[3; 7]).is_uniform());
+    assert!(!fldr::Generator::new(&[3, 3, 0]).is_uniform());
+}
+
+#[test]
+fn test_split_at() {
+    let generator = fldr::Generator::new(&[4, 1, 0, 3, 5, 2]);
+    let split = generator.split_at(2);
+    assert_eq!(split.mid(), 2);
+    assert_eq!(split.gate_weights(), [5, 10]);
+    assert_eq!(split.left_weights(), [4, 1]);
+    assert_eq!(split.right_weights(), [0, 3, 5, 2]);
+    assert!(!split.is_left_built() && !split.is_right_built());
+
+    let mut coin = XorShiftCoin::new(6);
+    for _ in 0..1_000 {
+        assert_ne!(split.sample(&mut coin), 2);
+    }
+    assert!(split.is_left_built() && split.is_right_built());
+
+    // A half may have a single non-zero weight, or none at all.
+    let generator = fldr::Generator::new(&[1, 2, 3]);
+    let single = generator.split_at(1);
+    let empty = generator.split_at(0);
+    let mut histogram = [0; 3];
+    for _ in 0..6_000 {
+        histogram[single.sample(&mut coin)] += 1;
+        assert!(empty.sample(&mut coin) < 3);
+    }
+    assert!(!empty.is_left_built());
+    assert!(histogram[0] > 800 && histogram[0] < 1_200, "{histogram:?}");
+
+    let full = generator.split_at(3);
+    full.sample(&mut coin);
+    assert!(!full.is_right_built());
+}
+
+#[test]
+fn test_from_cumulative() {
+    let generator = fldr::Generator::from_cumulative(&[2, 2, 7, 10]);
+    assert_eq!(generator.weights(), [2, 0, 5, 3]);
+}
+
+#[test]
+fn test_sparse_generator() {
+    let generator = fldr::sparse::SparseGenerator::new(&[
+        (u64::MAX, 3),
+        (1 << 40, 0),
+        (17, 2),
+        (1 << 50, 1),
+        (17, 2),
+    ]);
+    assert_eq!(generator.indices(), [17, 1 << 50, u64::MAX]);
+
+    let mut coin = XorShiftCoin::new(14);
+    let mut counts = [0; 3];
+    for _ in 0..8_000 {
+        let i = generator.sample(&mut coin);
+        counts[generator.indices().binary_search(&i).unwrap()] += 1;
+    }
+    assert!(counts[0] > counts[2] && counts[2] > counts[1], "{counts:?}");
+}
+
+#[test]
+fn test_probability_table() {
+    let table = fldr::Generator::new(&[2, 0, 4, 6, 8]).probability_table();
+    assert_eq!(table.fraction(0), (1, 10));
+    assert_eq!(table.fraction(1), (0, 1));
+    assert_eq!(
+        format!("{table:.3}"),
+        "0: 1/10 = 0.100\n1: 0/1 = 0.000\n2: 1/5 = 0.200\n3: 3/10 = 0.300\n4: 2/5 = 0.400\n"
+    );
+
+    let table = fldr::Generator::new(&[1, 2]).probability_table();
+    assert_eq!(
+        table.to_string(),
+        "0: 1/3 = 0.3333333333...\n1: 2/3 = 0.6666666666...\n"
+    );
+}
+
+#[test]
+fn test_exact_probabilities() {
+    let dyadic = fldr::Generator::new(&[1, 0, 1, 2, 12]);
+    assert_eq!(
+        dyadic.exact_probabilities(),
+        vec![(1, 4), (0, 0), (1, 4), (1, 3), (3, 2)]
+    );
+
+    // With padding, the leaves and the padding weight together cover the whole tree.
+    for weights in [vec![1, 2], vec![3, 5, 7, 11, 13], vec![1, 1_000_000]] {
+        let generator = fldr::Generator::new(&weights);
+        let depth = generator.depth() as u32;
+        let covered: u128 = generator
+            .exact_probabilities()
+            .into_iter()
+            .map(|(numerator, exponent)| u128::from(numerator) << (depth - exponent))
+            .sum();
+        assert_eq!(covered + generator.padding_weight(), 1 << depth);
+    }
+}
+
+#[test]
+fn test_builder_limits() {
+    let builder = fldr::Generator::builder()
+        .max_depth(10)
+        .max_matrix_bytes(4096);
+    assert_eq!(builder.build(&[1, 2, 3]).unwrap().weights(), [1, 2, 3]);
+    assert_eq!(builder.build(&[1024, 1]).err(), Some(fldr::Error::TooDeep));
+    assert_eq!(builder.build(&[1; 600]).err(), Some(fldr::Error::TooLarge));
+    assert_eq!(
+        builder.build(&[0, 1]).err(),
+        Some(fldr::Error::TooFewNonZeroWeights)
+    );
+}
+
+#[test]
+fn test_packed_tree_size() {
+    // 10,000 weights of one take a word per level and a word per leaf, including the six leaves
+    // of the padding weight 6,384, rather than a dense matrix of 10,002 words per level.
+    let distribution = vec![1; 10_000];
+    let bytes = 10_020 * core::mem::size_of::<usize>();
+    let build = |max| {
+        fldr::Generator::builder()
+            .max_matrix_bytes(max)
+            .build(&distribution)
+    };
+    assert!(build(bytes).is_ok());
+    assert_eq!(build(bytes - 1).err(), Some(fldr::Error::TooLarge));
+}
+
+#[test]
+fn test_compact_labels() {
+    let distribution = [60, 25, 10, 5, 0, 7];
+    let generator = fldr::Generator::new(&distribution);
+    let builder = fldr::Generator::builder();
+    let narrow: fldr::Generator<u16> = builder.build_compact(&distribution).unwrap();
+    let medium: fldr::Generator<u32> = builder.build_compact(&distribution).unwrap();
+    assert_eq!(narrow.weights(), distribution);
+    assert_eq!(medium.weights(), distribution);
+
+    // Narrower labels change only how the tree is stored, not the choices made while sampling.
+    let mut a = XorShiftCoin::new(4);
+    let mut b = XorShiftCoin::new(4);
+    let mut c = XorShiftCoin::new(4);
+    for _ in 0..10_000 {
+        let i = generator.sample(&mut a);
+        assert_eq!(i, narrow.sample(&mut b));
+        assert_eq!(i, medium.sample(&mut c));
+    }
+
+    // The matrix is measured in labels, and every label, including the padding label, must fit.
+    let bytes = 10_020 * core::mem::size_of::<u16>();
+    let distribution = vec![1; 10_000];
+    let builder = builder.max_matrix_bytes(bytes);
+    assert!(builder.build_compact::<u16>(&distribution).is_ok());
+    assert_eq!(
+        builder.build(&distribution).err(),
+        Some(fldr::Error::TooLarge)
+    );
+    assert!(fldr::Generator::builder()
+        .build_compact::<u16>(&[1; 65_534])
+        .is_ok());
+    assert_eq!(
+        fldr::Generator::builder()
+            .build_compact::<u16>(&[1; 65_535])
+            .err(),
+        Some(fldr::Error::TooManyBuckets)
+    );
+}
+
+#[test]
+fn test_invalid_distributions_are_errors() {
+    assert_eq!(
+        fldr::Generator::try_new(&[]).err(),
+        Some(fldr::Error::TooFewNonZeroWeights)
+    );
+    assert_eq!(
+        fldr::Generator::try_new(&[0, 2, 0]).err(),
+        Some(fldr::Error::TooFewNonZeroWeights)
+    );
+    assert_eq!(
+        fldr::Generator::try_new(&[usize::MAX, 1]).err(),
+        Some(fldr::Error::WeightSumOverflow)
+    );
+}
+
+#[test]
+fn test_try_from_containers() {
+    use std::time::Duration;
+
+    let weights = |generator: Result<fldr::Generator, fldr::Error>| generator.map(|g| g.weights());
+    assert_eq!(weights([3, 1].try_into()), Ok(vec![3, 1]));
+    assert_eq!(weights(vec![0, 2, 5].try_into()), Ok(vec![0, 2, 5]));
+    assert_eq!(weights([7_u32, 0, 1][..].try_into()), Ok(vec![7, 0, 1]));
+    assert_eq!(weights([1_u64, 1][..].try_into()), Ok(vec![1, 1]));
+    assert_eq!(
+        weights([Duration::from_micros(2), Duration::from_nanos(500)][..].try_into()),
+        Ok(vec![2_000, 500])
+    );
+    assert_eq!(
+        weights([5_usize, 0][..].try_into()),
+        Err(fldr::Error::TooFewNonZeroWeights)
+    );
+    assert_eq!(
+        weights([Duration::MAX, Duration::MAX][..].try_into()),
+        Err(fldr::Error::WeightSumOverflow)
+    );
+}
+
+#[test]
+fn test_from_fixed_units() {
+    let weights = |generator: Result<fldr::Generator, fldr::Error>| generator.map(|g| g.weights());
+    assert_eq!(
+        weights(fldr::Generator::from_percentages(&[70, 25, 5])),
+        Ok(vec![70, 25, 5])
+    );
+    assert_eq!(
+        weights(fldr::Generator::from_basis_points(&[9_950, 0, 50])),
+        Ok(vec![9_950, 0, 50])
+    );
+
+    let error = weights(fldr::Generator::from_percentages(&[70, 25, 4])).unwrap_err();
+    assert_eq!(
+        error,
+        fldr::Error::WrongTotal {
+            expected: 100,
+            actual: 99
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "The weights must sum to 100, but sum to 99."
+    );
+    assert_eq!(
+        weights(fldr::Generator::from_basis_points(&[100])),
+        Err(fldr::Error::WrongTotal {
+            expected: 10_000,
+            actual: 100
+        })
+    );
+    assert_eq!(
+        weights(fldr::Generator::from_percentages(&[100, 0])),
+        Err(fldr::Error::TooFewNonZeroWeights)
+    );
+}
+
+/// The output of `codegen::static_generator_source("LOOT", &[60, 25, 10, 5])`.
+mod loot {
+    include!("fixtures/loot.rs");
+}
+
+#[test]
+fn test_static_generator_codegen() {
+    assert_eq!(
+        fldr::codegen::static_generator_source("LOOT", &[60, 25, 10, 5]),
+        include_str!("fixtures/loot.rs")
+    );
+
+    // The embedded sampler makes exactly the same choices as a heap-allocated one.
+    let generator = fldr::Generator::new(&[60, 25, 10, 5]);
+    assert_eq!(loot::LOOT.bucket_count(), 4);
+    let mut a = XorShiftCoin::new(31);
+    let mut b = XorShiftCoin::new(31);
+    let mut c = XorShiftCoin::new(31);
+    for _ in 0..10_000 {
+        let i = loot::LOOT.sample(&mut a);
+        assert_eq!(i, generator.sample(&mut b));
+        assert_eq!(i, generator.as_static().sample(&mut c));
+    }
+}
+
+#[test]
+fn test_new_uniform_matches_general_construction() {
+    for n in [2, 3, 4, 5, 7, 8, 100, 1_023, 1_024, 1_025] {
+        let uniform = fldr::Generator::new_uniform(n);
+        let general = fldr::Generator::new(&vec![1; n]);
+        assert_eq!(uniform.weights(), vec![1; n]);
+        let mut a = XorShiftCoin::new(n as u64);
+        let mut b = XorShiftCoin::new(n as u64);
+        for _ in 0..1_000 {
+            assert_eq!(uniform.sample(&mut a), general.sample(&mut b));
+        }
+    }
+}
+
+#[test]
+fn test_from_runs() {
+    let runs = fldr::Generator::from_runs(&[(100, 1), (0, 2), (1, 1_000), (7, 0)]);
+    let mut expected = vec![100, 0, 0];
+    expected.extend([1; 1_000]);
+    assert_eq!(runs.weights(), expected);
+
+    let dense = fldr::Generator::new(&expected);
+    let mut a = XorShiftCoin::new(32);
+    let mut b = XorShiftCoin::new(32);
+    for _ in 0..1_000 {
+        assert_eq!(runs.sample(&mut a), dense.sample(&mut b));
+    }
+}
+
+#[test]
+fn test_grid_2d() {
+    // A 2x3 heatmap whose corners are hot and whose centre column is cold.
+    let grid = fldr::grid::Grid2D::new(2, 3, &[4, 0, 1, 1, 0, 4]);
+    assert_eq!((grid.rows(), grid.cols()), (2, 3));
+
+    let mut coin = XorShiftCoin::new(31);
+    let mut counts = [[0; 3]; 2];
+    for _ in 0..10_000 {
+        let (row, col) = grid.sample(&mut coin);
+        counts[row][col] += 1;
+    }
+    assert_eq!((counts[0][1], counts[1][1]), (0, 0));
+    assert!((3_800..4_200).contains(&counts[0][0]));
+    assert!((3_800..4_200).contains(&counts[1][2]));
+    assert!((800..1_200).contains(&counts[0][2]));
+
+    let hotspot = fldr::grid::Grid2D::new(2, 2, &[0, 0, 7, 0]);
+    assert_eq!(hotspot.sample(&mut coin), (1, 0));
+}
+
+#[test]
+fn test_generator_2d() {
+    // Proportional rows share one tree, and an empty row is never sampled.
+    let weights = [1, 2, 3, 2, 4, 6, 0, 0, 0, 3, 0, 0];
+    let generator = fldr::grid::Generator2D::new(4, 3, &weights);
+    assert_eq!((generator.rows(), generator.cols()), (4, 3));
+    assert_eq!(generator.distinct_rows(), 2);
+
+    let mut coin = XorShiftCoin::new(61);
+    let mut counts = [[0_usize; 3]; 4];
+    for _ in 0..21_000 {
+        let (row, col) = generator.sample(&mut coin);
+        counts[row][col] += 1;
+    }
+    assert_eq!(counts[2], [0, 0, 0]);
+    assert_eq!(&counts[3][1..], &[0, 0]);
+    for (cell, &weight) in counts.iter().flatten().zip(&weights) {
+        let expected = weight * 1_000;
+        assert!(cell.abs_diff(expected) < 250 + expected / 10);
+    }
+}
+
+#[test]
+fn test_sample_adjusted() {
+    let generator = fldr::Generator::new(&[1, 1, 2]);
+    let mut coin = XorShiftCoin::new(37);
+
+    // Halving the last index makes every index equally likely, and index 0 is excluded outright.
+    let mut counts = [0; 3];
+    for _ in 0..30_000 {
+        counts[generator.sample_adjusted(&mut coin, |i| [(0, 1), (1, 1), (1, 2)][i])] += 1;
+    }
+    assert_eq!(counts[0], 0);
+    assert!((14_500..15_500).contains(&counts[1]), "{counts:?}");
+
+    let unchanged = (0..1_000)
+        .map(|_| generator.sample_adjusted(&mut coin, |_| (3, 3)))
+        .filter(|&i| i == 2)
+        .count();
+    assert!((450..550).contains(&unchanged));
+}
+
+#[test]
+fn test_from_generic_weights() {
+    // Narrow weights may sum past their own type.
+    let narrow = fldr::Generator::from_weights(&[200_u8, 0, 200, 100]);
+    assert_eq!(narrow.weights(), vec![200, 0, 200, 100]);
+
+    // The same weights at any width build the same tree.
+    let (mut a, mut b) = (XorShiftCoin::new(41), XorShiftCoin::new(41));
+    let wide = fldr::Generator::from_weights(&[200_u128, 0, 200, 100]);
+    for _ in 0..1_000 {
+        assert_eq!(narrow.sample(&mut a), wide.sample(&mut b));
+    }
+
+    // Weights wider than a `usize` are sampled exactly.
+    let huge = fldr::Generator::from_weights(&[u128::MAX / 4, u128::MAX / 4 * 3]);
+    let mut coin = XorShiftCoin::new(43);
+    let ones = (0..10_000).filter(|_| huge.sample(&mut coin) == 1).count();
+    assert!((7_300..7_700).contains(&ones));
+
+    assert_eq!(
+        fldr::Generator::try_from_weights(&[u128::MAX, 1]).err(),
+        Some(fldr::Error::WeightSumOverflow)
+    );
+    assert_eq!(
+        fldr::Generator::try_from_weights(&[0_u16, 9]).err(),
+        Some(fldr::Error::TooFewNonZeroWeights)
+    );
+}
+
+#[test]
+fn test_sample_multinomial() {
+    let mut coin = XorShiftCoin::new(47);
+
+    // Few draws are sampled one at a time, and many are split across the tree.
+    for n in [0, 10, 100_000_000] {
+        let counts = fldr::Generator::new(&[1, 2, 0, 5]).sample_multinomial(&mut coin, n);
+        assert_eq!(counts.iter().sum::<usize>(), n);
+        assert_eq!(counts[2], 0);
+    }
+    let counts = fldr::Generator::new(&[1, 2, 0, 5]).sample_multinomial(&mut coin, 100_000_000);
+    for (count, expected) in counts.iter().zip([12_500_000, 25_000_000, 0, 62_500_000]) {
+        assert!(count.abs_diff(expected) < 20_000);
+    }
+
+    // Draws that reach the padding label are restarted, so the counts remain exact.
+    let counts = fldr::Generator::new(&[3, 0, 7, 1]).sample_multinomial(&mut coin, 11_000_000);
+    assert_eq!(counts.iter().sum::<usize>(), 11_000_000);
+    for (count, expected) in counts.iter().zip([3_000_000, 0, 7_000_000, 1_000_000]) {
+        assert!(count.abs_diff(expected) < 10_000);
+    }
+
+    // The spread of the counts matches a binomial, whose variance here is 250,000.
+    let fair = fldr::Generator::new(&[1, 1]);
+    let heads: Vec<_> = (0..400)
+        .map(|_| fair.sample_multinomial(&mut coin, 1_000_000)[0] as f64)
+        .collect();
+    let mean = heads.iter().sum::<f64>() / 400.0;
+    let variance = heads.iter().map(|h| (h - mean).powi(2)).sum::<f64>() / 399.0;
+    assert!((mean - 500_000.0).abs() < 100.0);
+    assert!((180_000.0..320_000.0).contains(&variance));
+}
+
+#[test]
+fn test_sample_masked() {
+    let mut coin = XorShiftCoin::new(53);
+    let generator = fldr::Generator::new(&[1, 2, 3, 4]);
+    let allowed = [true, false, true, false];
+    let mut counts = [0; 4];
+    for _ in 0..8_000 {
+        counts[generator.sample_masked(&mut coin, &allowed)] += 1;
+    }
+    assert_eq!((counts[1], counts[3]), (0, 0));
+    assert!((1_800..2_200).contains(&counts[0]));
+
+    // A mask allowing almost none of the weight falls back to the allowed weights directly.
+    let skewed = fldr::Generator::new(&[1_000_000_000, 1, 1]);
+    for _ in 0..100 {
+        assert_ne!(skewed.sample_masked(&mut coin, &[false, true, true]), 0);
+        assert_eq!(skewed.sample_masked(&mut coin, &[false, false, true]), 2);
+    }
+}
+
+#[test]
+fn test_mixture() {
+    let mut coin = XorShiftCoin::new(59);
+    let low = fldr::Generator::new(&[1, 1, 0, 0]);
+    let high = fldr::Generator::new(&[0, 0, 1, 1]);
+    let mut mixture = fldr::mixture::Mixture::new(vec![low, high], &[1, 3]);
+
+    let high_draws = (0..4_000)
+        .filter(|_| mixture.sample(&mut coin) >= 2)
+        .count();
+    assert!((2_850..3_150).contains(&high_draws));
+
+    // Updating one component or its weight leaves the other as it was.
+    mixture.set_component(0, fldr::Generator::new(&[0, 1, 0, 1]));
+    mixture.set_weight(1, 0);
+    assert_eq!(mixture.weights(), &[1, 0]);
+    assert_eq!(mixture.components()[0].weights(), vec![0, 1, 0, 1]);
+    for _ in 0..100 {
+        let (component, item) = mixture.sample_component(&mut coin);
+        assert_eq!(component, 0);
+        assert!(item % 2 == 1);
+    }
+}
+
+#[test]
+fn test_sample_counting() {
+    // Without padding, the bucket of weight two sits one level above the others.
+    let generator = fldr::Generator::new(&[1, 1, 2]);
+    let mut coin = XorShiftCoin::new(67);
+    for _ in 0..1_000 {
+        let (i, flips) = generator.sample_counting(&mut coin);
+        assert_eq!(flips, if i == 2 { 1 } else { 2 });
+    }
+
+    // The counting coin totals the flips across samples.
+    let mut counting = fldr::coin::CountingCoin::new(&mut coin);
+    let total: u64 = (0..1_000)
+        .map(|_| u64::from(generator.sample_counting(&mut counting).1))
+        .sum();
+    assert_eq!(counting.flips(), total);
+}
+
+#[test]
+fn test_tree_statistics() {
+    // A dyadic distribution never rejects.
+    let dyadic = fldr::Generator::new(&[1, 1, 2]);
+    assert_eq!(dyadic.depth(), 2);
+    assert_eq!(dyadic.leaf_counts(), vec![1, 2]);
+    assert_eq!(dyadic.padding_weight(), 0);
+    assert!((dyadic.expected_flips() - 1.5).abs() < 1e-12);
+
+    // Weights summing to three are padded to four, so a quarter of the passes are rejected.
+    let padded = fldr::Generator::new(&[1, 2]);
+    assert_eq!(padded.depth(), 2);
+    assert_eq!(padded.leaf_counts(), vec![1, 2]);
+    assert_eq!(padded.padding_weight(), 1);
+    assert!((padded.expected_flips() - 2.0).abs() < 1e-12);
+
+    let generator = fldr::Generator::new(&[3, 5, 7, 11, 13]);
+    let mut coin = XorShiftCoin::new(71);
+    let flips: u32 = (0..100_000)
+        .map(|_| generator.sample_counting(&mut coin).1)
+        .sum();
+    let mean = f64::from(flips) / 100_000.;
+    assert!((mean - generator.expected_flips()).abs() < 0.05, "{mean}");
+}
+
+#[test]
+fn test_to_dot() {
+    // Weights summing to three: bucket 1 is a leaf of the first level, and bucket 0 shares the
+    // second level with the padding label.
+    let dot = fldr::Generator::new(&[1, 2]).to_dot();
+    assert_eq!(
+        dot,
+        "digraph ddg {\n    \
+         root [shape=point];\n    \
+         n0_0 [shape=box, label=\"1\"];\n    \
+         root -> n0_0 [label=\"0\"];\n    \
+         n0_1 [shape=point];\n    \
+         root -> n0_1 [label=\"1\"];\n    \
+         n1_0 [shape=box, label=\"0\"];\n    \
+         n0_1 -> n1_0 [label=\"0\"];\n    \
+         n1_1 [shape=box, style=dashed, label=\"reject\"];\n    \
+         n1_1 -> root [style=dashed, constraint=false];\n    \
+         n0_1 -> n1_1 [label=\"1\"];\n\
+         }\n"
+    );
+}
+
+#[test]
+fn test_debug_levels() {
+    let generator = fldr::Generator::new(&[1, 2, 0, 4]);
+    assert_eq!(
+        format!("{generator:?}"),
+        "Generator { bucket_count: 4, levels: [[3], [1], [0, pad]] }"
+    );
+    assert_eq!(
+        format!("{:#?}", fldr::Generator::new(&[1, 1])),
+        "Generator {\n    bucket_count: 2,\n    levels: [\n        [\n            0,\n            1,\n        ],\n    ],\n}"
+    );
+}
+
+#[test]
+fn test_binary_encoding() {
+    let generator = fldr::Generator::new(&[3, 0, 7, 1, 12]);
+    let bytes = generator.to_bytes();
+    assert_eq!(&bytes[..8], b"FLDT\x01\0\0\0");
+    let decoded = fldr::Generator::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.weights(), generator.weights());
+    assert_eq!(decoded.to_bytes(), bytes);
+
+    // Compact trees encode the same words as their `usize` counterparts.
+    let compact: fldr::Generator<u16> = fldr::Generator::builder()
+        .build_compact(&[3, 0, 7, 1, 12])
+        .unwrap();
+    assert_eq!(compact.to_bytes(), bytes);
+
+    // Truncation, trailing data, and any flipped bit are all rejected.
+    assert_eq!(
+        fldr::Generator::from_bytes(&bytes[..bytes.len() - 1]).err(),
+        Some(fldr::Error::InvalidEncoding)
+    );
+    let mut extended = bytes.clone();
+    extended.push(0);
+    assert!(fldr::Generator::from_bytes(&extended).is_err());
+    for i in 0..bytes.len() {
+        let mut corrupted = bytes.clone();
+        corrupted[i] ^= 0x10;
+        assert!(fldr::Generator::from_bytes(&corrupted).is_err(), "byte {i}");
+    }
+}
+
+#[test]
+fn test_reference_text() {
+    // Weights summing to eleven are padded by five: the levels hold the bits 8, 4, 2, and 1.
+    let generator = fldr::Generator::new(&[3, 0, 7, 1]);
+    let text = generator.to_reference_text();
+    assert_eq!(
+        text,
+        "4 11 4 5\n0 2 2 4\n0 2 0 0\n0 4 2 2\n0 0 0 3\n0 0 0 4\n0 0 0 0\n"
+    );
+    let loaded = fldr::Generator::from_reference_text(&text).unwrap();
+    assert_eq!(loaded.weights(), generator.weights());
+
+    // Unused entries are ignored, and any whitespace separates the values.
+    let loose = "4 11 4 5 0 2 2 4 9 2 0 0 9 4 2 2 9 9 9 3 9 9 9 4 9 9 9 9";
+    let loaded = fldr::Generator::from_reference_text(loose).unwrap();
+    assert_eq!(loaded.weights(), vec![3, 0, 7, 1]);
+
+    // Inconsistent headers and malformed text are rejected.
+    assert_eq!(
+        fldr::Generator::from_reference_text(&text.replace("4 11 4 5", "4 12 4 5")).err(),
+        Some(fldr::Error::MalformedTree)
+    );
+    assert_eq!(
+        fldr::Generator::from_reference_text(&text[..text.len() - 3]).err(),
+        Some(fldr::Error::InvalidEncoding)
+    );
+    assert_eq!(
+        fldr::Generator::from_reference_text("2 2 1 x 2 0 1 0").err(),
+        Some(fldr::Error::InvalidEncoding)
+    );
+}
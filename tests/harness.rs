@@ -0,0 +1,44 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+mod common;
+
+use common::XorShiftCoin;
+use fast_loaded_dice_roller as fldr;
+use fldr::harness;
+
+#[test]
+fn test_flips_within_entropy_bound() {
+    let mut coin = XorShiftCoin::new(84);
+    let regressions = harness::regressions(&mut coin, 20_000, harness::DEFAULT_TOLERANCE);
+    assert!(regressions.is_empty(), "{regressions:#?}");
+}
+
+#[test]
+fn test_entropy_of_uniform_and_dyadic() {
+    assert!((harness::entropy(&[1; 8]) - 3.).abs() < 1e-12);
+    assert!((harness::entropy(&[0, 2, 1, 1]) - 1.5).abs() < 1e-12);
+
+    // A dyadic distribution is sampled with exactly its entropy in flips.
+    let report = harness::measure(&[2, 1, 1], &mut XorShiftCoin::new(85), 100_000);
+    assert!((report.mean_flips - 1.5).abs() < 0.02, "{report:?}");
+}
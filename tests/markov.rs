@@ -0,0 +1,177 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+mod common;
+
+use common::XorShiftCoin;
+use fast_loaded_dice_roller as fldr;
+
+#[test]
+fn test_hidden_markov_model() {
+    // A dishonest casino: state 0 rolls a fair die, state 1 a die loaded towards six, and state 1
+    // can never emit a one.
+    let model = fldr::markov::HiddenMarkovModel::new(
+        &[1, 0],
+        &[[95, 5], [10, 90]],
+        &[[1, 1, 1, 1, 1, 1], [0, 1, 1, 1, 1, 5]],
+    );
+    assert_eq!(model.state_count(), 2);
+    assert_eq!(model.symbol_count(), 6);
+
+    let mut coin = XorShiftCoin::new(5);
+    let (states, symbols) = model.sample_sequence(&mut coin, 100_000);
+    assert_eq!(states.len(), 100_000);
+    assert_eq!(symbols.len(), 100_000);
+    assert_eq!(states[0], 0);
+    assert!(states
+        .iter()
+        .zip(&symbols)
+        .all(|(&state, &symbol)| symbol < 6 && (state == 0 || symbol != 0)));
+
+    // The chain is stationary at 2/3 in the fair state.
+    let fair = states.iter().filter(|&&s| s == 0).count() as f64 / states.len() as f64;
+    assert!((fair - 2.0 / 3.0).abs() < 0.03, "{fair}");
+}
+
+#[test]
+#[should_panic(expected = "Every transition row must have a non-zero weight.")]
+fn test_hidden_markov_model_dead_state() {
+    let _ = fldr::markov::HiddenMarkovModel::new(&[1, 1], &[[1, 1], [0, 0]], &[[1], [1]]);
+}
+
+#[test]
+fn test_weighted_graph_walks() {
+    // Node 0 leads to node 1 three times as often as to node 2, node 1 leads back to node 0, and
+    // node 2 is a dead end.
+    let graph = fldr::graph::WeightedGraph::new(vec![vec![(1, 3), (2, 1)], vec![(0, 1)], vec![]]);
+    assert_eq!(graph.node_count(), 3);
+    assert_eq!(graph.edges(0), &[(1, 3), (2, 1)]);
+
+    let mut coin = XorShiftCoin::new(11);
+    assert_eq!(graph.step(&mut coin, 2), None);
+    assert_eq!(graph.step(&mut coin, 1), Some(0));
+
+    let walk = graph.walk(&mut coin, 0, 1_000);
+    assert_eq!(walk.last(), Some(&2));
+    assert!(walk
+        .windows(2)
+        .all(|pair| graph.edges(pair[0]).iter().any(|&(to, _)| to == pair[1])));
+    assert_eq!(graph.walk(&mut coin, 1, 1), vec![1]);
+
+    // Dead ends restart the walk, so it always has the requested length.
+    let walk = graph.walk_with_restart(&mut coin, 0, 40_000, 0, 1);
+    assert_eq!(walk.len(), 40_000);
+    let from_zero: Vec<_> = walk
+        .windows(2)
+        .filter(|pair| pair[0] == 0)
+        .map(|pair| pair[1])
+        .collect();
+    let to_one = from_zero.iter().filter(|&&to| to == 1).count() as f64;
+    assert!((to_one / from_zero.len() as f64 - 0.75).abs() < 0.02);
+
+    // Restarting before every step never leaves the start.
+    assert!(graph
+        .walk_with_restart(&mut coin, 1, 100, 1, 1)
+        .iter()
+        .all(|&node| node == 1));
+}
+
+#[test]
+#[should_panic(expected = "Every edge must target a node of the graph.")]
+fn test_weighted_graph_dangling_edge() {
+    let _ = fldr::graph::WeightedGraph::new(vec![vec![(1, 1)]]);
+}
+
+#[test]
+fn test_grammar_expansion() {
+    use fldr::grammar::{Grammar, Symbol::*};
+
+    // <name> ::= <syllable> <name> (weight 1) | <syllable> (weight 2)
+    // <syllable> ::= "ka" (weight 3) | "ro" (weight 1)
+    let grammar = Grammar::new(vec![
+        vec![
+            (1, vec![Nonterminal(1), Nonterminal(0)]),
+            (2, vec![Nonterminal(1)]),
+        ],
+        vec![(3, vec![Terminal("ka")]), (1, vec![Terminal("ro")])],
+    ]);
+    assert_eq!(grammar.rule_count(), 2);
+
+    let mut coin = XorShiftCoin::new(17);
+    let mut syllables = 0;
+    let mut ka = 0;
+    for _ in 0..10_000 {
+        // Each name is at most 8 syllables long, since the syllable rule nests one level deeper.
+        let Some(name) = grammar.expand(&mut coin, 0, 9) else {
+            continue;
+        };
+        assert!((1..=8).contains(&name.len()));
+        syllables += name.len();
+        ka += name.iter().filter(|&&s| s == "ka").count();
+    }
+    assert!((ka as f64 / syllables as f64 - 0.75).abs() < 0.02);
+
+    // The syllable rule needs one level, and the name rule needs at least two.
+    assert!(grammar.expand(&mut coin, 1, 1).is_some());
+    assert_eq!(grammar.expand(&mut coin, 0, 1), None);
+}
+
+#[test]
+#[should_panic(expected = "Every nonterminal must refer to a rule of the grammar.")]
+fn test_grammar_undefined_rule() {
+    let _ = fldr::grammar::Grammar::new(vec![vec![(
+        1,
+        vec![fldr::grammar::Symbol::<()>::Nonterminal(1)],
+    )]]);
+}
+
+#[test]
+fn test_transition_sampler() {
+    // A gambler's ruin: states 0 and 3 are absorbing, the middle states move up twice as often as
+    // down, and the empty row of state 3 counts as absorbing too.
+    let transitions = [[1, 0, 0, 0], [1, 0, 2, 0], [0, 1, 0, 2], [0, 0, 0, 0]];
+    let mut chain = fldr::markov::TransitionSampler::new(&transitions, 1);
+    assert_eq!(chain.state_count(), 4);
+    assert!(chain.is_absorbing(0) && chain.is_absorbing(3));
+    assert!(!chain.is_absorbing(1) && !chain.is_absorbed());
+
+    let mut coin = XorShiftCoin::new(13);
+    let mut ruined = 0;
+    for _ in 0..7_000 {
+        chain.set_state(1);
+        while !chain.is_absorbed() {
+            let previous = chain.state();
+            assert_eq!(chain.step(&mut coin).abs_diff(previous), 1);
+        }
+        assert_eq!(chain.step(&mut coin), chain.state());
+        ruined += usize::from(chain.state() == 0);
+    }
+
+    // Starting one step from ruin, the chain is ruined with probability 3/7.
+    assert!((2_800..3_200).contains(&ruined));
+}
+
+#[test]
+#[should_panic(expected = "Every transition row must have one weight per state.")]
+fn test_transition_sampler_ragged_rows() {
+    let _ = fldr::markov::TransitionSampler::new(&[vec![1, 1], vec![1]], 0);
+}
@@ -0,0 +1,93 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+mod common;
+
+use std::{
+    collections::HashMap,
+    sync::{atomic::AtomicU64, atomic::Ordering, Arc, Mutex},
+};
+
+use common::XorShiftCoin;
+use fast_loaded_dice_roller as fldr;
+use fldr::FairCoin;
+use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+
+/// A minimal recorder that only keeps the totals of counters.
+#[derive(Default)]
+struct CounterRecorder {
+    counters: Mutex<HashMap<String, Arc<AtomicU64>>>,
+}
+
+impl CounterRecorder {
+    fn get(&self, name: &str) -> u64 {
+        self.counters
+            .lock()
+            .unwrap()
+            .get(name)
+            .map_or(0, |c| c.load(Ordering::Relaxed))
+    }
+}
+
+impl Recorder for CounterRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        let mut counters = self.counters.lock().unwrap();
+        Counter::from_arc(counters.entry(key.name().to_owned()).or_default().clone())
+    }
+
+    fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::noop()
+    }
+}
+
+/// Counts flips independently of the crate's instrumentation.
+struct TallyCoin(XorShiftCoin, u64);
+
+impl FairCoin for TallyCoin {
+    fn flip(&mut self) -> bool {
+        self.1 += 1;
+        self.0.flip()
+    }
+}
+
+#[test]
+fn test_sampling_metrics() {
+    let recorder = CounterRecorder::default();
+    let mut coin = TallyCoin(XorShiftCoin::new(4), 0);
+    metrics::with_local_recorder(&recorder, || {
+        let generator = fldr::Generator::new(&[3, 5, 7]);
+        for _ in 0..500 {
+            generator.sample(&mut coin);
+        }
+    });
+
+    assert_eq!(recorder.get(fldr::metrics::SAMPLES_TOTAL), 500);
+    assert_eq!(recorder.get(fldr::metrics::FLIPS_TOTAL), coin.1);
+}
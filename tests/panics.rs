@@ -53,3 +53,39 @@ fn test_lone_weight_distribution() {
     let test_distribution = [0, 2, 0, 0];
     let _generator = fldr::Generator::new(&test_distribution);
 }
+
+#[test]
+#[should_panic(expected = "The sum of the weights must fit in a `usize`.")]
+fn test_overflowing_sum_distribution() {
+    // Test a distribution whose total weight cannot be represented on the target.
+    let test_distribution = [usize::MAX, 1];
+    let _generator = fldr::Generator::new(&test_distribution);
+}
+
+#[test]
+#[should_panic(expected = "The cumulative weights must be nondecreasing.")]
+fn test_decreasing_cumulative_distribution() {
+    // Test cumulative weights that decrease, which would imply a negative weight.
+    let _generator = fldr::Generator::from_cumulative(&[1, 5, 4]);
+}
+
+#[test]
+#[should_panic(expected = "The distribution must have at least two non-zero weights.")]
+fn test_uniform_single_outcome() {
+    // Test a uniform distribution over a single outcome.
+    let _generator = fldr::Generator::new_uniform(1);
+}
+
+#[test]
+#[should_panic(expected = "The distribution must have at least two non-zero weights.")]
+fn test_runs_single_nonzero_weight() {
+    // Test run-length encoded weights where every non-zero weight is in a single bucket.
+    let _generator = fldr::Generator::from_runs(&[(0, 1_000), (5, 1), (3, 0)]);
+}
+
+#[test]
+#[should_panic(expected = "The sum of the weights must fit in a `usize`.")]
+fn test_runs_overflow() {
+    // Test run-length encoded weights whose sum overflows.
+    let _generator = fldr::Generator::from_runs(&[(usize::MAX / 2, 3)]);
+}
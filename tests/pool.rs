@@ -0,0 +1,67 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+mod common;
+
+use common::XorShiftCoin;
+use fast_loaded_dice_roller as fldr;
+
+fn test_rows() -> Vec<Vec<usize>> {
+    (1..50).map(|i| vec![i, 3, 0, i % 7, 2 * i + 1]).collect()
+}
+
+#[test]
+fn test_pool_matches_generators() {
+    let rows = test_rows();
+    let pool = fldr::pool::GeneratorPool::new(&rows);
+    assert_eq!(pool.len(), rows.len());
+
+    // Sampling each row of the pool must consume the same coin flips as a standalone generator.
+    let mut pool_coin = XorShiftCoin::new(7);
+    let mut generator_coin = XorShiftCoin::new(7);
+    for (i, row) in rows.iter().enumerate() {
+        let generator = fldr::Generator::new(row);
+        for _ in 0..100 {
+            assert_eq!(
+                pool.sample(i, &mut pool_coin),
+                generator.sample(&mut generator_coin)
+            );
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_parallel_pool_matches_sequential() {
+    let rows = test_rows();
+    let sequential = fldr::pool::GeneratorPool::new(&rows);
+    let parallel = fldr::pool::GeneratorPool::par_new(&rows);
+
+    let mut sequential_coin = XorShiftCoin::new(11);
+    let mut parallel_coin = XorShiftCoin::new(11);
+    for i in 0..rows.len() {
+        assert_eq!(
+            sequential.sample(i, &mut sequential_coin),
+            parallel.sample(i, &mut parallel_coin)
+        );
+    }
+}
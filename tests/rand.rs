@@ -161,3 +161,87 @@ fn test_entropy() {
         );
     }
 }
+
+#[test]
+fn test_fork_is_deterministic() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let new_roller = || {
+        fldr::roller::Roller::new(
+            fldr::Generator::new(&[1, 2, 3, 4]),
+            fldr::rand::RngCoin::new(StdRng::seed_from_u64(42)),
+        )
+    };
+    let (mut parent_a, mut parent_b) = (new_roller(), new_roller());
+    let (mut child_a, mut child_b) = (parent_a.fork(), parent_b.fork());
+
+    let child_samples: Vec<_> = (0..1_000).map(|_| child_a.sample()).collect();
+    let parent_samples: Vec<_> = (0..1_000).map(|_| parent_a.sample()).collect();
+    assert_eq!(
+        child_samples,
+        (0..1_000).map(|_| child_b.sample()).collect::<Vec<_>>()
+    );
+    assert_ne!(child_samples, parent_samples);
+}
+
+#[test]
+fn test_bit_order() {
+    use fldr::{coin::BitOrder, FairCoin};
+    use rand::{rngs::mock::StepRng, RngCore};
+
+    // Every flip must match the corresponding bit of the underlying `u64` stream.
+    let mut rng = StepRng::new(0x8000_0000_0000_0001, 0x0123_4567_89AB_CDEF);
+    let blocks: Vec<_> = (0..3).map(|_| rng.next_u64()).collect();
+    for (order, bit) in [
+        (
+            BitOrder::LsbFirst,
+            (|block: u64, i: u32| block >> i & 1 > 0) as fn(u64, u32) -> bool,
+        ),
+        (BitOrder::MsbFirst, |block: u64, i: u32| {
+            block >> (63 - i) & 1 > 0
+        }),
+    ] {
+        let mut coin = fldr::rand::RngCoin::with_bit_order(
+            StepRng::new(0x8000_0000_0000_0001, 0x0123_4567_89AB_CDEF),
+            order,
+        );
+        for &block in &blocks {
+            for i in 0..64 {
+                assert_eq!(coin.flip(), bit(block, i), "{order:?}, bit {i}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_sample_weighted() {
+    let mut counts = [0usize; 3];
+    for _ in 0..30_000 {
+        counts[fldr::sample_weighted(&[1, 0, 2])] += 1;
+    }
+    assert_eq!(counts[1], 0);
+    assert!(counts[2].abs_diff(20_000) < 600, "{counts:?}");
+
+    // Many distinct distributions can be sampled from the same thread.
+    for n in 2..200 {
+        assert!(fldr::sample_weighted(&vec![1; n]) < n);
+    }
+}
+
+#[test]
+fn test_distribution() {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    let generator = fldr::Generator::new(&[1, 0, 3]);
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut counts = [0usize; 3];
+    for _ in 0..20_000 {
+        counts[rng.sample(&generator)] += 1;
+    }
+    counts[Distribution::sample(&generator, &mut rng)] += 1;
+    for i in (&mut rng).sample_iter(&generator).take(9_999) {
+        counts[i] += 1;
+    }
+    assert_eq!(counts[1], 0);
+    assert!(counts[2].abs_diff(22_500) < 600, "{counts:?}");
+}
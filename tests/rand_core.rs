@@ -0,0 +1,83 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use fast_loaded_dice_roller as fldr;
+
+/// A minimal `RngCore` that only depends on `rand_core`, as an embedded RNG might.
+struct SplitMix64(u64);
+
+impl rand_core::RngCore for SplitMix64 {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand_core::impls::fill_bytes_via_next(self, dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_rng_core_coin() {
+    let mut coin = fldr::rand_core::RngCoin::new(SplitMix64(7));
+    let generator = fldr::Generator::new(&[1, 3]);
+    let ones = (0..10_000)
+        .filter(|_| generator.sample(&mut coin) == 1)
+        .count();
+    assert!((7_300..7_700).contains(&ones));
+}
+
+impl rand_core::SeedableRng for SplitMix64 {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self(u64::from_le_bytes(seed))
+    }
+}
+
+#[test]
+fn test_roller_fork_with_rng_core() {
+    let roller = |seed| {
+        fldr::roller::Roller::new(
+            fldr::Generator::new(&[1, 2, 3]),
+            fldr::rand_core::RngCoin::new(SplitMix64(seed)),
+        )
+    };
+    let (mut parent_a, mut parent_b) = (roller(9), roller(9));
+    let (mut child_a, mut child_b) = (parent_a.fork(), parent_b.fork());
+    let samples =
+        |r: &mut fldr::roller::Roller<_>| (0..100).map(|_| r.sample()).collect::<Vec<_>>();
+    assert_eq!(samples(&mut child_a), samples(&mut child_b));
+    assert_ne!(samples(&mut child_a), samples(&mut parent_a));
+}
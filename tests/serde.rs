@@ -0,0 +1,155 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use fast_loaded_dice_roller as fldr;
+
+type Roller = fldr::roller::Roller<fldr::rand::RngCoin<ChaCha8Rng>>;
+
+#[test]
+fn test_roller_checkpoint_resumes_stream() {
+    let mut roller = Roller::new(
+        fldr::Generator::new(&[3, 1, 4, 1, 5]),
+        fldr::rand::RngCoin::new(ChaCha8Rng::seed_from_u64(7)),
+    );
+
+    // Take a checkpoint in the middle of a block of buffered bits.
+    for _ in 0..37 {
+        roller.sample();
+    }
+    let checkpoint = serde_json::to_string(&roller).unwrap();
+    let expected: Vec<_> = (0..1_000).map(|_| roller.sample()).collect();
+
+    let mut restored: Roller = serde_json::from_str(&checkpoint).unwrap();
+    assert_eq!(restored.generator().weights(), [3, 1, 4, 1, 5]);
+    let resumed: Vec<_> = (0..1_000).map(|_| restored.sample()).collect();
+    assert_eq!(resumed, expected);
+}
+
+#[test]
+fn test_invalid_checkpoint_is_an_error() {
+    let checkpoint = serde_json::to_value(Roller::new(
+        fldr::Generator::new(&[1, 1]),
+        fldr::rand::RngCoin::new(ChaCha8Rng::seed_from_u64(7)),
+    ))
+    .unwrap();
+    let mut corrupted = checkpoint.clone();
+    corrupted["generator"]["level_label_matrix"] = serde_json::json!([1, 1, 1, 0]);
+    assert!(serde_json::from_value::<Roller>(corrupted).is_err());
+}
+
+#[test]
+fn test_wide_roller_checkpoint() {
+    // The weights of this generator do not fit in a `usize`, but its tree can still be saved.
+    let roller = Roller::new(
+        fldr::Generator::from_weights(&[1_u128 << 100, 1]),
+        fldr::rand::RngCoin::new(ChaCha8Rng::seed_from_u64(7)),
+    );
+    let checkpoint = serde_json::to_value(&roller).unwrap();
+    assert_eq!(
+        checkpoint["generator"],
+        serde_json::to_value(roller.generator()).unwrap()
+    );
+    let mut restored: Roller = serde_json::from_value(checkpoint).unwrap();
+    assert_eq!(restored.sample(), 0);
+}
+
+#[test]
+fn test_generator_round_trip() {
+    use rand::RngCore;
+
+    // A large tree is restored as is rather than rebuilt from its weights.
+    let mut rng = ChaCha8Rng::seed_from_u64(11);
+    let weights: Vec<_> = (0..10_000)
+        .map(|_| rng.next_u32() as usize % 1_000)
+        .collect();
+    let generator = fldr::Generator::new(&weights);
+    let cached = serde_json::to_string(&generator).unwrap();
+    let restored: fldr::Generator = serde_json::from_str(&cached).unwrap();
+    assert_eq!(restored.weights(), weights);
+
+    let mut a = fldr::rand::RngCoin::new(ChaCha8Rng::seed_from_u64(13));
+    let mut b = fldr::rand::RngCoin::new(ChaCha8Rng::seed_from_u64(13));
+    for _ in 0..1_000 {
+        assert_eq!(generator.sample(&mut a), restored.sample(&mut b));
+    }
+}
+
+#[test]
+fn test_malformed_generator_is_an_error() {
+    let tree = serde_json::to_value(fldr::Generator::new(&[1, 3])).unwrap();
+    assert_eq!(
+        tree,
+        serde_json::json!({
+            "bucket_count": 2,
+            "level_label_matrix": [1, 1, 2, 0, 1],
+        })
+    );
+
+    let corrupt = |key: &str, value: serde_json::Value| {
+        let mut tree = tree.clone();
+        tree[key] = value;
+        serde_json::from_value::<fldr::Generator>(tree).is_err()
+    };
+    // A label out of bounds, a level that leaves nodes open, and a level that is cut short.
+    assert!(corrupt(
+        "level_label_matrix",
+        serde_json::json!([1, 3, 2, 0, 1])
+    ));
+    assert!(corrupt(
+        "level_label_matrix",
+        serde_json::json!([1, 1, 1, 0])
+    ));
+    assert!(corrupt(
+        "level_label_matrix",
+        serde_json::json!([1, 1, 2, 0])
+    ));
+    assert!(corrupt("bucket_count", serde_json::json!(0)));
+
+    // A bucket count whose bound on the open nodes would overflow a `usize`.
+    assert!(corrupt("bucket_count", serde_json::json!(usize::MAX - 1)));
+
+    // A tree whose weights would not fit in a `u128`, with one leaf on each of 128 levels before
+    // a last level of two leaves.
+    let deep = |levels: usize| {
+        let mut matrix: Vec<usize> = [1, 0].repeat(levels - 1);
+        matrix.extend([2, 0, 1]);
+        serde_json::json!({ "bucket_count": 2, "level_label_matrix": matrix })
+    };
+    assert!(serde_json::from_value::<fldr::Generator>(deep(128)).is_ok());
+    assert!(serde_json::from_value::<fldr::Generator>(deep(129)).is_err());
+
+    // Trees that the constructors never produce: ones with a single bucket of non-zero weight,
+    // and ones whose padding weight is at least half of the tree.
+    let tree = |bucket_count: usize, matrix: &[usize]| serde_json::json!({ "bucket_count": bucket_count, "level_label_matrix": matrix });
+    assert!(serde_json::from_value::<fldr::Generator>(tree(1, &[2, 0, 1])).is_err());
+    let error = serde_json::from_value::<fldr::Generator>(tree(1, &[1, 0, 2, 0, 1]))
+        .err()
+        .unwrap();
+    assert!(error
+        .to_string()
+        .contains(&fldr::Error::TooFewNonZeroWeights.to_string()));
+    assert!(serde_json::from_value::<fldr::Generator>(tree(2, &[1, 2, 2, 0, 1])).is_err());
+    assert!(serde_json::from_value::<fldr::Generator>(tree(2, &[1, 0, 2, 1, 2])).is_ok());
+}
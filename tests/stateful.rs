@@ -0,0 +1,303 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+mod common;
+
+use common::XorShiftCoin;
+use fast_loaded_dice_roller as fldr;
+use fldr::anti_repeat::AntiRepeat;
+
+#[test]
+fn test_anti_repeat_excludes_history() {
+    let mut sampler = AntiRepeat::exclude(fldr::Generator::new(&[8, 1, 1, 1]), 2);
+    let mut coin = XorShiftCoin::new(22);
+    let samples: Vec<_> = (0..1_000).map(|_| sampler.sample(&mut coin)).collect();
+    for window in samples.windows(3) {
+        assert!(
+            window[2] != window[0] && window[2] != window[1],
+            "{window:?}"
+        );
+    }
+}
+
+#[test]
+fn test_anti_repeat_down_weights_history() {
+    // The heavy bucket is repeated with its weight halved: 4 of 6 instead of 8 of 10.
+    let mut sampler = AntiRepeat::down_weight(fldr::Generator::new(&[8, 2]), 1, 1, 2);
+    let mut coin = XorShiftCoin::new(23);
+    let mut repeats = 0;
+    let mut previous_heavy = 0;
+    let mut previous = sampler.sample(&mut coin);
+    for _ in 0..100_000 {
+        let i = sampler.sample(&mut coin);
+        if previous == 0 {
+            previous_heavy += 1;
+            repeats += usize::from(i == 0);
+        }
+        previous = i;
+    }
+    let rate = repeats as f64 / previous_heavy as f64;
+    assert!((rate - 2. / 3.).abs() < 0.01, "Rate: {rate}");
+}
+
+#[test]
+fn test_anti_repeat_penalty_of_one() {
+    // A penalty of one leaves the distribution, and the stream of samples, unchanged.
+    let generator = fldr::Generator::new(&[1, 2, 3]);
+    let mut sampler = AntiRepeat::down_weight(generator.clone(), 2, 1, 1);
+    let mut a = XorShiftCoin::new(25);
+    let mut b = XorShiftCoin::new(25);
+    for _ in 0..1_000 {
+        assert_eq!(sampler.sample(&mut a), generator.sample(&mut b));
+    }
+}
+
+#[test]
+fn test_shuffle_bag_cycles() {
+    let weights = [3, 0, 1, 5, 2];
+    let mut bag = fldr::shuffle_bag::ShuffleBag::new(&weights);
+    let mut coin = XorShiftCoin::new(24);
+    for _ in 0..20 {
+        // Every cycle contains each index exactly as many times as its weight.
+        let mut counts = [0; 5];
+        for _ in 0..11 {
+            counts[bag.sample(&mut coin)] += 1;
+        }
+        assert_eq!(counts, weights);
+        assert_eq!(bag.remaining(), 0);
+    }
+}
+
+#[test]
+fn test_smooth_wrr() {
+    // The classic nginx example: weights 5, 1, 1 are spread as "a a b a c a a".
+    let generator = fldr::Generator::new(&[5, 1, 1]);
+    let order: Vec<_> = generator.smooth_wrr().take(14).collect();
+    assert_eq!(order, [0, 0, 1, 0, 2, 0, 0, 0, 0, 1, 0, 2, 0, 0]);
+}
+
+#[test]
+fn test_weighted_actions() {
+    let mut counts = [0; 3];
+    let [idle, patrol, attack] = &mut counts;
+    let mut actions = fldr::actions::WeightedActions::new()
+        .with(70, || *idle += 1)
+        .with(20, || *patrol += 1);
+    actions.push(10, || *attack += 1);
+    assert_eq!(actions.len(), 3);
+
+    let mut coin = XorShiftCoin::new(24);
+    for _ in 0..100_000 {
+        actions.run(&mut coin);
+    }
+    drop(actions);
+    assert_eq!(counts.iter().sum::<usize>(), 100_000);
+    for (count, expected) in counts.into_iter().zip([70_000, 20_000, 10_000]) {
+        assert!(count.abs_diff(expected) < 1_000, "{counts:?}");
+    }
+}
+
+#[test]
+fn test_fault_injector_shared_between_threads() {
+    use fldr::chaos::{Fault, FaultInjector, FaultWeights};
+
+    let injector = FaultInjector::new(
+        FaultWeights {
+            none: 90,
+            latency: 6,
+            error: 3,
+            corrupt: 1,
+        },
+        7,
+    );
+    let counts = std::thread::scope(|s| {
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut counts = [0; 4];
+                    for _ in 0..25_000 {
+                        counts[injector.decide() as usize] += 1;
+                    }
+                    counts
+                })
+            })
+            .collect();
+        workers.into_iter().fold([0; 4], |mut total, worker| {
+            let counts: [usize; 4] = worker.join().unwrap();
+            total.iter_mut().zip(counts).for_each(|(t, c)| *t += c);
+            total
+        })
+    });
+    for (count, expected) in counts.into_iter().zip([90_000, 6_000, 3_000, 1_000]) {
+        assert!(count.abs_diff(expected) < 400, "{counts:?}");
+    }
+
+    // Disabled faults are never injected.
+    let disabled = FaultInjector::new(
+        FaultWeights {
+            none: 1,
+            ..FaultWeights::default()
+        },
+        0,
+    );
+    assert!((0..1_000).all(|_| disabled.decide() == Fault::None));
+    let mut coin = XorShiftCoin::new(25);
+    assert_eq!(disabled.decide_with(&mut coin), Fault::None);
+}
+
+#[test]
+fn test_dynamic_sampler() {
+    use fldr::{dynamic::DynamicSampler, sampler::DiscreteSampler};
+
+    fn frequencies(sampler: &impl DiscreteSampler, coin: &mut XorShiftCoin) -> Vec<f64> {
+        let mut counts = vec![0; sampler.bucket_count()];
+        for _ in 0..100_000 {
+            counts[sampler.sample(coin)] += 1;
+        }
+        counts.into_iter().map(|c| c as f64 / 100_000.).collect()
+    }
+
+    let mut coin = XorShiftCoin::new(26);
+    let mut sampler = DynamicSampler::new(&[1, 0, 3]);
+    let p = frequencies(&sampler, &mut coin);
+    assert!((p[0] - 0.25).abs() < 0.01 && p[1] == 0., "{p:?}");
+
+    // Raising and lowering weights takes effect immediately.
+    sampler.set_weight(1, 4);
+    sampler.set_weight(2, 0);
+    assert_eq!((sampler.weights(), sampler.total()), (&[1, 4, 0][..], 5));
+    let p = frequencies(&sampler, &mut coin);
+    assert!((p[1] - 0.8).abs() < 0.01 && p[2] == 0., "{p:?}");
+
+    // A static generator over the same weights samples the same distribution.
+    let p = frequencies(&fldr::Generator::new(&[1, 4, 0]), &mut coin);
+    assert!((p[1] - 0.8).abs() < 0.01 && p[2] == 0., "{p:?}");
+}
+
+#[test]
+fn test_annealing_selector_sharpens_as_it_cools() {
+    let mut selector = fldr::annealing::AnnealingSelector::new(vec![0., 1., 3.], 1e6, 16);
+    let mut coin = XorShiftCoin::new(23);
+
+    // Hot enough that every move is equally likely.
+    assert_eq!(selector.weights(), &[65_536, 65_536, 65_536]);
+    let mut counts = [0; 3];
+    for _ in 0..30_000 {
+        counts[selector.sample(&mut coin)] += 1;
+    }
+    assert!(counts.iter().all(|&c| (9_000..11_000).contains(&c)));
+
+    // At unit temperature the weights follow `exp(score - 3)`.
+    selector.set_temperature(1.);
+    assert_eq!(selector.weights(), &[3_263, 8_869, 65_536]);
+
+    // Cold enough that only the best move remains.
+    while selector.temperature() > 0.01 {
+        selector.cool(0.5);
+    }
+    assert_eq!(selector.weights(), &[0, 0, 65_536]);
+    assert!((0..100).all(|_| selector.sample(&mut coin) == 2));
+
+    selector.set_score(0, 4.);
+    assert!((0..100).all(|_| selector.sample(&mut coin) == 0));
+}
+
+#[test]
+fn test_plackett_luce_rankings() {
+    let model = fldr::ranking::PlackettLuce::new(&[6, 0, 3, 1]);
+    assert_eq!(model.rankable(), 3);
+    let mut coin = XorShiftCoin::new(29);
+
+    // The first place follows the weights, and the second follows the weights of the rest.
+    let mut first = [0; 4];
+    let mut second_after_zero = [0; 4];
+    for _ in 0..20_000 {
+        let ranking = model.sample_ranking(&mut coin);
+        let mut sorted = ranking.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, vec![0, 2, 3]);
+
+        first[ranking[0]] += 1;
+        if ranking[0] == 0 {
+            second_after_zero[ranking[1]] += 1;
+        }
+    }
+    assert!((11_500..12_500).contains(&first[0]));
+    assert!((5_600..6_400).contains(&first[2]));
+    let after_zero = f64::from(second_after_zero[2]) / f64::from(first[0]);
+    assert!((after_zero - 0.75).abs() < 0.02);
+
+    assert_eq!(model.sample_top(&mut coin, 0), Vec::<usize>::new());
+    assert_eq!(model.sample_top(&mut coin, 2).len(), 2);
+}
+
+#[test]
+fn test_sample_distinct() {
+    let generator = fldr::Generator::new(&[6, 0, 3, 1]);
+    let mut coin = XorShiftCoin::new(37);
+
+    // Pairs are distinct, and the second index follows the weights of the indices that remain.
+    let mut second_after_zero = [0usize; 4];
+    let mut first_zero = 0usize;
+    for _ in 0..20_000 {
+        let pair = generator.sample_distinct(&mut coin, 2);
+        assert_eq!(pair.len(), 2);
+        assert_ne!(pair[0], pair[1]);
+        assert!(!pair.contains(&1));
+        if pair[0] == 0 {
+            first_zero += 1;
+            second_after_zero[pair[1]] += 1;
+        }
+    }
+    assert!(first_zero.abs_diff(12_000) < 500, "{first_zero}");
+    let after_zero = second_after_zero[2] as f64 / first_zero as f64;
+    assert!((after_zero - 0.75).abs() < 0.02, "{after_zero}");
+
+    let mut all = generator.sample_distinct(&mut coin, 3);
+    all.sort_unstable();
+    assert_eq!(all, [0, 2, 3]);
+}
+
+#[test]
+fn test_weighted_shuffle() {
+    let generator = fldr::Generator::new(&[0, 8, 0, 1, 0, 1]);
+    let mut coin = XorShiftCoin::new(41);
+    let mut first = [0usize; 6];
+    let mut zero_last = [0usize; 6];
+    for _ in 0..10_000 {
+        let permutation = generator.weighted_shuffle(&mut coin);
+        let mut sorted = permutation.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, [0, 1, 2, 3, 4, 5]);
+
+        // The buckets with a weight of zero come last, in a uniformly random order.
+        let mut tail = permutation[3..].to_vec();
+        tail.sort_unstable();
+        assert_eq!(tail, [0, 2, 4]);
+        first[permutation[0]] += 1;
+        zero_last[permutation[5]] += 1;
+    }
+    assert!(first[1].abs_diff(8_000) < 250, "{first:?}");
+    for i in [0, 2, 4] {
+        assert!(zero_last[i].abs_diff(3_333) < 250, "{zero_last:?}");
+    }
+}
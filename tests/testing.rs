@@ -0,0 +1,71 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+mod common;
+
+use common::XorShiftCoin;
+use fast_loaded_dice_roller as fldr;
+use fldr::testing::WeightedValues;
+use proptest::{
+    strategy::{Strategy, ValueTree},
+    test_runner::TestRunner,
+};
+
+fn charsets() -> WeightedValues<&'static str> {
+    WeightedValues::new([(90, "ascii"), (9, "latin-1"), (0, "never"), (1, "emoji")])
+}
+
+#[test]
+fn test_pick_follows_weights() {
+    let charsets = charsets();
+    let mut coin = XorShiftCoin::new(34);
+    let picks: Vec<_> = (0..100_000).map(|_| *charsets.pick(&mut coin)).collect();
+    let ascii = picks.iter().filter(|&&c| c == "ascii").count();
+    assert!(ascii.abs_diff(90_000) < 1_000, "{ascii}");
+    assert!(!picks.contains(&"never"));
+
+    let mut g = quickcheck::Gen::new(10);
+    assert!((0..1_000).all(|_| *charsets.pick_quickcheck(&mut g) != "never"));
+}
+
+#[test]
+fn test_strategy_shrinks_to_first_failing_value() {
+    // A property that fails for every charset but ASCII shrinks to the first one that fails.
+    let mut runner = TestRunner::deterministic();
+    let result = runner.run(&charsets(), |charset| {
+        proptest::prop_assert_eq!(charset, "ascii");
+        Ok(())
+    });
+    match result {
+        Err(proptest::test_runner::TestError::Fail(_, minimal)) => assert_eq!(minimal, "latin-1"),
+        other => panic!("{other:?}"),
+    }
+
+    // Simplifying always moves towards the front of the list.
+    let mut runner = TestRunner::deterministic();
+    let mut tree = charsets().new_tree(&mut runner).unwrap();
+    while tree.current() == "ascii" {
+        tree = charsets().new_tree(&mut runner).unwrap();
+    }
+    while tree.simplify() {}
+    assert_eq!(tree.current(), "ascii");
+}
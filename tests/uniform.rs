@@ -0,0 +1,128 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+mod common;
+
+use common::XorShiftCoin;
+use fast_loaded_dice_roller as fldr;
+use fldr::uniform::{HeadTailGenerator, Uniform};
+
+#[test]
+fn test_uniform_is_uniform() {
+    let uniform = Uniform::new(6);
+    let mut coin = XorShiftCoin::new(15);
+    let mut counts = [0; 6];
+    for _ in 0..60_000 {
+        counts[uniform.sample(&mut coin)] += 1;
+    }
+    assert!(
+        counts.iter().all(|&c| (9_500..10_500).contains(&c)),
+        "{counts:?}"
+    );
+}
+
+#[test]
+fn test_uniform_near_max() {
+    let uniform = Uniform::new(usize::MAX);
+    let mut coin = XorShiftCoin::new(16);
+    for _ in 0..100 {
+        assert!(uniform.sample(&mut coin) < usize::MAX);
+    }
+}
+
+#[test]
+fn test_head_tail() {
+    // Two heavy specials followed by a million equally weighted common items.
+    let generator = HeadTailGenerator::new(&[500_000, 250_000], 1_000_000, 1);
+    assert_eq!(generator.len(), 1_000_002);
+
+    let mut coin = XorShiftCoin::new(18);
+    let mut head_counts = [0; 2];
+    let mut tail_count = 0;
+    for _ in 0..70_000 {
+        match generator.sample(&mut coin) {
+            i @ 0..=1 => head_counts[i] += 1,
+            i => {
+                assert!(i < 1_000_002);
+                tail_count += 1;
+            }
+        }
+    }
+    assert!(
+        (19_000..21_000).contains(&head_counts[0]),
+        "{head_counts:?}"
+    );
+    assert!((9_000..11_000).contains(&head_counts[1]), "{head_counts:?}");
+    assert!((39_000..41_000).contains(&tail_count), "{tail_count}");
+}
+
+#[test]
+fn test_tail_only() {
+    let generator = HeadTailGenerator::new(&[0, 0], 5, 3);
+    let mut coin = XorShiftCoin::new(19);
+    for _ in 0..1_000 {
+        assert!((2..7).contains(&generator.sample(&mut coin)));
+    }
+}
+
+#[test]
+fn test_piecewise_uniform() {
+    let sampler = fldr::uniform::PiecewiseUniform::new(&[
+        (1_000..2_000, 3),
+        (5..5, 0),
+        (1 << 40..(1 << 40) + 10, 1),
+    ]);
+    let mut coin = XorShiftCoin::new(20);
+    let mut low = 0;
+    for _ in 0..40_000 {
+        match sampler.sample(&mut coin) {
+            1_000..2_000 => low += 1,
+            x => assert!(((1 << 40)..(1 << 40) + 10).contains(&x)),
+        }
+    }
+    assert!((29_000..31_000).contains(&low), "{low}");
+}
+
+#[test]
+fn test_partitioned_generator() {
+    // A hundred thousand outcomes, where every third is twice as likely as the others.
+    let len = 100_003;
+    let mut generator =
+        fldr::partitioned::PartitionedGenerator::new(len, |i| 1 + usize::from(i % 3 == 0))
+            .with_cache_capacity(16);
+    assert_eq!(generator.len(), len);
+    assert_eq!(generator.partition_len(), 317);
+
+    let mut coin = XorShiftCoin::new(19);
+    let mut heavy = 0;
+    for _ in 0..20_000 {
+        let i = generator.sample(&mut coin);
+        assert!(i < len);
+        heavy += usize::from(i % 3 == 0);
+    }
+    assert!((heavy as f64 / 20_000. - 0.5).abs() < 0.015, "{heavy}");
+
+    // Zero weights are never sampled, including partitions and indices left with all the mass.
+    let weights = [0, 0, 3, 0, 1];
+    let mut generator = fldr::partitioned::PartitionedGenerator::new(5, |i| weights[i]);
+    assert!((0..1_000).all(|_| [2, 4].contains(&generator.sample(&mut coin))));
+}
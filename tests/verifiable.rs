@@ -0,0 +1,74 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use fast_loaded_dice_roller as fldr;
+use fldr::FairCoin;
+
+#[test]
+fn test_beacon_draw_is_reproducible() {
+    let generator = fldr::Generator::new(&[1, 2, 3, 4, 5]);
+    let pulse = [0xA5; 32];
+    let draw = generator.draw_from_beacon(4_242, &pulse);
+    assert_eq!(draw, generator.draw_from_beacon(4_242, &pulse));
+    assert_eq!(draw.round, 4_242);
+    assert!(draw.flips > 0);
+
+    // Replaying the same number of flips from a fresh coin reproduces the draw.
+    let mut coin = fldr::beacon::BeaconCoin::new(4_242, &pulse);
+    assert_eq!(generator.sample(&mut coin), draw.index);
+    assert_eq!(coin.flips(), draw.flips);
+}
+
+#[test]
+fn test_beacon_rounds_are_independent() {
+    let flips = |round| {
+        let mut coin = fldr::beacon::BeaconCoin::new(round, b"same pulse");
+        (0..512).map(|_| coin.flip()).collect::<Vec<_>>()
+    };
+    assert_ne!(flips(1), flips(2));
+    let ones = flips(1).iter().filter(|&&b| b).count();
+    assert!((200..312).contains(&ones), "{ones}");
+}
+
+#[test]
+fn test_provably_fair_transcript() {
+    let generator = fldr::Generator::new(&[10, 1, 4, 7]);
+    let server_seed = b"operator secret";
+    let commitment = fldr::provably_fair::commit(server_seed);
+
+    let transcripts = (0..32)
+        .map(|nonce| generator.draw_provably_fair(server_seed, b"player", nonce))
+        .collect::<Vec<_>>();
+    for transcript in &transcripts {
+        assert_eq!(transcript.commitment, commitment);
+        assert!(transcript.verify(&generator));
+    }
+    assert!(transcripts.windows(2).any(|w| w[0].index != w[1].index));
+
+    let mut forged = transcripts[0].clone();
+    forged.server_seed = b"another secret".to_vec();
+    assert!(!forged.verify(&generator));
+
+    let mut forged = transcripts[0].clone();
+    forged.index = (forged.index + 1) % 4;
+    assert!(!forged.verify(&generator));
+}
@@ -0,0 +1,67 @@
+// MIT License
+
+// Copyright (c) 2023 Ryan Andersen
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use fast_loaded_dice_roller as fldr;
+use fldr::FairCoin;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[test]
+fn test_zeroized_coin_forgets_its_seed() {
+    fn assert_zeroize_on_drop<T: ZeroizeOnDrop>(_: &T) {}
+
+    let mut a = fldr::procgen::RegionCoin::new(1, &[2, 3], 4);
+    let mut b = fldr::procgen::RegionCoin::new(5, &[6], 7);
+    assert_zeroize_on_drop(&a);
+    a.flip();
+    a.zeroize();
+    b.zeroize();
+    let flips =
+        |coin: &mut fldr::procgen::RegionCoin| (0..128).map(|_| coin.flip()).collect::<Vec<_>>();
+    assert_eq!(flips(&mut a), flips(&mut b));
+}
+
+#[test]
+fn test_generator_drop_with_live_clones() {
+    // Dropping one clone must not clear the tree shared with the others.
+    let generator = fldr::Generator::new(&[3, 1, 4]);
+    let clone = generator.clone();
+    drop(generator);
+    assert_eq!(clone.weights(), [3, 1, 4]);
+}
+
+#[test]
+fn test_edits_with_live_clones() {
+    // Replacing the tree of one clone must not clear the tree still shared with the others.
+    let mut generator = fldr::Generator::new(&[3, 1, 4]);
+    let clone = generator.clone();
+    generator.set_weight(1, 2);
+    assert_eq!(generator.weights(), [3, 2, 4]);
+    assert_eq!(clone.weights(), [3, 1, 4]);
+    generator.set_weight(0, 1);
+    assert_eq!(generator.weights(), [1, 2, 4]);
+
+    let clone = generator.clone();
+    generator.push_weight(5);
+    generator.remove_bucket(0);
+    assert_eq!(generator.weights(), [2, 4, 5]);
+    assert_eq!(clone.weights(), [1, 2, 4]);
+}